@@ -0,0 +1,264 @@
+use anyhow::anyhow;
+use indexmap::IndexMap;
+
+use crate::{Error, Value};
+
+impl Value {
+    /// Borrow the string if value is `Value::Str`.
+    pub fn as_str(&self) -> Option<&str> {
+        match self.unshared() {
+            Value::Str(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Return the bool if value is `Value::Bool`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.unshared() {
+            Value::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Return the integer if value is an integer that fits in `i64`.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.unshared() {
+            Value::I8(v) => Some(*v as i64),
+            Value::I16(v) => Some(*v as i64),
+            Value::I32(v) => Some(*v as i64),
+            Value::I64(v) => Some(*v),
+            Value::I128(v) => i64::try_from(*v).ok(),
+            Value::U8(v) => Some(*v as i64),
+            Value::U16(v) => Some(*v as i64),
+            Value::U32(v) => Some(*v as i64),
+            Value::U64(v) => i64::try_from(*v).ok(),
+            Value::U128(v) => i64::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Return the integer if value is an integer that fits in `u64`.
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.unshared() {
+            Value::I8(v) => u64::try_from(*v).ok(),
+            Value::I16(v) => u64::try_from(*v).ok(),
+            Value::I32(v) => u64::try_from(*v).ok(),
+            Value::I64(v) => u64::try_from(*v).ok(),
+            Value::I128(v) => u64::try_from(*v).ok(),
+            Value::U8(v) => Some(*v as u64),
+            Value::U16(v) => Some(*v as u64),
+            Value::U32(v) => Some(*v as u64),
+            Value::U64(v) => Some(*v),
+            Value::U128(v) => u64::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+
+    /// Return the number as `f64` if value is a float or an integer.
+    ///
+    /// Integers that can't be represented exactly are rounded.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.unshared() {
+            Value::F32(v) => Some(*v as f64),
+            Value::F64(v) => Some(*v),
+            Value::I8(v) => Some(*v as f64),
+            Value::I16(v) => Some(*v as f64),
+            Value::I32(v) => Some(*v as f64),
+            Value::I64(v) => Some(*v as f64),
+            Value::I128(v) => Some(*v as f64),
+            Value::U8(v) => Some(*v as f64),
+            Value::U16(v) => Some(*v as f64),
+            Value::U32(v) => Some(*v as f64),
+            Value::U64(v) => Some(*v as f64),
+            Value::U128(v) => Some(*v as f64),
+            _ => None,
+        }
+    }
+
+    /// Borrow the entries if value is `Value::Map`.
+    pub fn as_map(&self) -> Option<&IndexMap<Value, Value>> {
+        match self.unshared() {
+            Value::Map(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Borrow the elements as a slice if value is a sequence.
+    ///
+    /// Covers `Seq`, `Tuple`, `TupleStruct` and the fields of `TupleVariant`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let v = Value::TupleStruct("Point", vec![Value::I32(1), Value::I32(2)]);
+    /// assert_eq!(v.as_seq().map(|s| s.len()), Some(2));
+    /// assert_eq!(Value::Str("a".to_string()).as_seq(), None);
+    /// ```
+    pub fn as_seq(&self) -> Option<&[Value]> {
+        match self.unshared() {
+            Value::Seq(v)
+            | Value::Tuple(v)
+            | Value::TupleStruct(_, v)
+            | Value::TupleVariant { fields: v, .. } => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Mutable counterpart of [`Value::as_seq`], covering the same variants.
+    ///
+    /// Elements can be added or removed through the returned `Vec`, which changes
+    /// the length of tuples as well.
+    pub fn as_seq_mut(&mut self) -> Option<&mut Vec<Value>> {
+        match self.unshared_mut() {
+            Value::Seq(v)
+            | Value::Tuple(v)
+            | Value::TupleStruct(_, v)
+            | Value::TupleVariant { fields: v, .. } => Some(v),
+            _ => None,
+        }
+    }
+}
+
+impl Value {
+    /// Fallible counterpart of [`Value::as_str`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let v = Value::Str("info".to_string());
+    /// assert_eq!(v.require_str()?, "info");
+    /// assert!(Value::I32(1).require_str().is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn require_str(&self) -> Result<&str, Error> {
+        self.as_str().ok_or_else(|| self.invalid_type("str"))
+    }
+
+    /// Fallible counterpart of [`Value::as_bool`].
+    pub fn require_bool(&self) -> Result<bool, Error> {
+        self.as_bool().ok_or_else(|| self.invalid_type("bool"))
+    }
+
+    /// Fallible counterpart of [`Value::as_i64`].
+    pub fn require_i64(&self) -> Result<i64, Error> {
+        self.as_i64().ok_or_else(|| self.invalid_type("i64"))
+    }
+
+    /// Fallible counterpart of [`Value::as_u64`].
+    pub fn require_u64(&self) -> Result<u64, Error> {
+        self.as_u64().ok_or_else(|| self.invalid_type("u64"))
+    }
+
+    /// Fallible counterpart of [`Value::as_f64`].
+    pub fn require_f64(&self) -> Result<f64, Error> {
+        self.as_f64().ok_or_else(|| self.invalid_type("f64"))
+    }
+
+    /// Fallible counterpart of [`Value::as_map`].
+    pub fn require_map(&self) -> Result<&IndexMap<Value, Value>, Error> {
+        self.as_map().ok_or_else(|| self.invalid_type("map"))
+    }
+
+    fn invalid_type(&self, expect: &str) -> Error {
+        Error(anyhow!("invalid type: {:?}, expect {}", self, expect))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_as() {
+        assert_eq!(Value::Str("a".to_string()).as_str(), Some("a"));
+        assert_eq!(Value::Bool(true).as_bool(), Some(true));
+        assert_eq!(Value::U64(u64::MAX).as_i64(), None);
+        assert_eq!(Value::I8(-1).as_i64(), Some(-1));
+        assert_eq!(Value::I8(-1).as_u64(), None);
+        assert_eq!(Value::U128(1).as_u64(), Some(1));
+        assert_eq!(Value::I32(2).as_f64(), Some(2.0));
+        assert_eq!(Value::Str("1".to_string()).as_f64(), None);
+        assert!(Value::Map(IndexMap::new()).as_map().is_some());
+    }
+
+    #[test]
+    fn test_as_shared() {
+        assert_eq!(Value::Str("a".to_string()).shared().as_str(), Some("a"));
+        assert_eq!(Value::Bool(true).shared().as_bool(), Some(true));
+        assert_eq!(Value::I64(-1).shared().as_i64(), Some(-1));
+        assert_eq!(Value::U8(1).shared().as_u64(), Some(1));
+        assert_eq!(Value::I32(2).shared().as_f64(), Some(2.0));
+        assert!(Value::Map(IndexMap::new()).shared().as_map().is_some());
+        assert_eq!(
+            Value::Tuple(vec![Value::Unit]).shared().as_seq(),
+            Some(&[Value::Unit][..])
+        );
+        // Only `Shared` is looked through, not other wrappers.
+        assert_eq!(
+            Value::Some(Box::new(Value::Str("a".to_string())))
+                .shared()
+                .as_str(),
+            None
+        );
+
+        // Mutating a shared seq doesn't affect other owners.
+        let shared = Value::Seq(vec![Value::U8(2), Value::U8(1)]).shared();
+        let mut v = shared.clone();
+        v.as_seq_mut().expect("must be seq").sort();
+        assert_eq!(v.as_seq(), Some(&[Value::U8(1), Value::U8(2)][..]));
+        assert_eq!(shared.as_seq(), Some(&[Value::U8(2), Value::U8(1)][..]));
+    }
+
+    #[test]
+    fn test_as_seq() {
+        let mut v = Value::Seq(vec![Value::I32(3), Value::I32(1), Value::I32(2)]);
+        v.as_seq_mut().expect("must be seq").sort();
+        assert_eq!(
+            v,
+            Value::Seq(vec![Value::I32(1), Value::I32(2), Value::I32(3)])
+        );
+
+        let v = Value::TupleVariant {
+            name: "E",
+            variant_index: 0,
+            variant: "A",
+            fields: vec![Value::U8(1), Value::U8(2)],
+        };
+        assert_eq!(v.as_seq(), Some(&[Value::U8(1), Value::U8(2)][..]));
+        assert_eq!(Value::Tuple(vec![]).as_seq(), Some(&[][..]));
+        assert!(Value::Map(IndexMap::new()).as_seq().is_none());
+        assert!(Value::Some(Box::new(Value::Seq(vec![])))
+            .as_seq_mut()
+            .is_none());
+    }
+
+    #[test]
+    fn test_require() -> anyhow::Result<()> {
+        let v = Value::Map(indexmap! {
+            Value::Str("level".to_string()) => Value::Str("info".to_string()),
+        });
+        let level = v.require_map()?[&Value::Str("level".to_string())].require_str()?;
+        assert_eq!(level, "info");
+
+        let err = Value::I32(1).require_str().unwrap_err();
+        assert_eq!(err.to_string(), "invalid type: I32(1), expect str");
+        assert_eq!(Value::Str("a".to_string()).shared().require_str()?, "a");
+        assert!(Value::Bool(false).shared().require_bool().is_ok());
+        assert_eq!(Value::I8(-1).shared().require_i64()?, -1);
+        assert_eq!(Value::U8(1).shared().require_u64()?, 1);
+        assert_eq!(Value::F32(0.5).shared().require_f64()?, 0.5);
+        assert!(Value::Map(IndexMap::new()).shared().require_map().is_ok());
+
+        let err = Value::I32(-1).require_u64().unwrap_err();
+        assert_eq!(err.to_string(), "invalid type: I32(-1), expect u64");
+        Ok(())
+    }
+}
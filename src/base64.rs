@@ -0,0 +1,133 @@
+use anyhow::anyhow;
+
+use crate::pretty::base64_encode;
+use crate::{Error, Value};
+
+impl Value {
+    /// Encode `Value::Bytes` into a `Value::Str` of standard padded base64.
+    ///
+    /// Serializers that are not aware of bytes, like `serde_json`, emit them as
+    /// an array of integers. Encoding them as base64 first is much more compact.
+    /// Other values are returned as is, use [`Value::visit_mut`] to encode nested
+    /// bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let v = Value::Bytes(b"foo".to_vec()).bytes_to_base64_str();
+    /// assert_eq!(v, Value::Str("Zm9v".to_string()));
+    /// ```
+    pub fn bytes_to_base64_str(self) -> Value {
+        match self {
+            Value::Bytes(v) => Value::Str(base64_encode(&v)),
+            v => v,
+        }
+    }
+
+    /// Decode a `Value::Str` of standard base64 into `Value::Bytes`.
+    ///
+    /// Padding is optional, whitespace is not allowed. Returns an error if the
+    /// value is not a `Value::Str` or not valid base64.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let v = Value::Str("Zm9v".to_string()).base64_str_to_bytes()?;
+    /// assert_eq!(v, Value::Bytes(b"foo".to_vec()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn base64_str_to_bytes(self) -> Result<Value, Error> {
+        match self {
+            Value::Str(v) => Ok(Value::Bytes(base64_decode(&v)?)),
+            v => Err(Error(anyhow!("invalid type: {:?}, expect base64 str", v))),
+        }
+    }
+}
+
+/// Decode standard base64 with optional padding.
+fn base64_decode(input: &str) -> Result<Vec<u8>, Error> {
+    let invalid = || Error(anyhow!("invalid base64: {:?}", input));
+
+    let data = input.as_bytes();
+    let unpadded = match data.iter().position(|b| *b == b'=') {
+        Some(pos) => {
+            // Padding must complete the last group and be at the end.
+            if !data.len().is_multiple_of(4)
+                || data.len() - pos > 2
+                || data[pos..].iter().any(|b| *b != b'=')
+            {
+                return Err(invalid());
+            }
+            &data[..pos]
+        }
+        None => data,
+    };
+    if unpadded.len() % 4 == 1 {
+        return Err(invalid());
+    }
+
+    let mut out = Vec::with_capacity(unpadded.len() * 3 / 4);
+    for chunk in unpadded.chunks(4) {
+        let mut n = 0u32;
+        for (idx, b) in chunk.iter().enumerate() {
+            let v = match b {
+                b'A'..=b'Z' => b - b'A',
+                b'a'..=b'z' => b - b'a' + 26,
+                b'0'..=b'9' => b - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                _ => return Err(invalid()),
+            };
+            n |= (v as u32) << (18 - 6 * idx);
+        }
+        // A chunk of `k` chars carries `k - 1` bytes.
+        for idx in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * idx)) as u8);
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_decode() -> anyhow::Result<()> {
+        assert_eq!(base64_decode("")?, b"");
+        assert_eq!(base64_decode("Zg==")?, b"f");
+        assert_eq!(base64_decode("Zm8=")?, b"fo");
+        assert_eq!(base64_decode("Zm8")?, b"fo");
+        assert_eq!(base64_decode("Zm9vYmFy")?, b"foobar");
+        assert_eq!(base64_decode("+/8=")?, vec![0xfb, 0xff]);
+
+        for v in ["Z", "Zg=", "Z===", "Zg==Zg==", "Zm9v!", "Zm 9v"] {
+            assert!(base64_decode(v).is_err(), "{}", v);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_base64_round_trip() -> anyhow::Result<()> {
+        for len in 0..=16 {
+            let bs: Vec<u8> = (0..len).map(|i| (i * 37 + 250) as u8).collect();
+            let v = Value::Bytes(bs.clone()).bytes_to_base64_str();
+            assert!(matches!(v, Value::Str(_)));
+            assert_eq!(v.base64_str_to_bytes()?, Value::Bytes(bs));
+        }
+
+        assert_eq!(Value::U8(1).bytes_to_base64_str(), Value::U8(1));
+        let err = Value::Str("Zm9v!".to_string())
+            .base64_str_to_bytes()
+            .unwrap_err();
+        assert_eq!(err.to_string(), r#"invalid base64: "Zm9v!""#);
+        assert!(Value::U8(1).base64_str_to_bytes().is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,453 @@
+use anyhow::anyhow;
+use indexmap::IndexMap;
+
+use crate::intern::intern;
+use crate::{Error, Value};
+
+/// Nesting limit while decoding, same as the default of [`Config::max_depth`].
+///
+/// [`Config::max_depth`]: crate::Config::max_depth
+const MAX_DEPTH: usize = 128;
+
+impl Value {
+    /// Encode value into a compact self-describing binary form.
+    ///
+    /// Every variant is kept as is, including integer widths and the names of
+    /// structs and variants, so [`Value::from_bytes`] returns an equal value.
+    ///
+    /// The layout is a variant tag byte followed by its content: numbers in
+    /// little endian, lengths as LEB128 varints, strings and bytes prefixed with
+    /// their length. The layout is not stable across versions of this crate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let v = Value::Seq(vec![Value::U8(1), Value::Str("a".to_string())]);
+    /// assert_eq!(Value::from_bytes(&v.to_bytes())?, v);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.encode(&mut buf);
+        buf
+    }
+
+    /// Decode value encoded by [`Value::to_bytes`].
+    ///
+    /// Names of structs and variants are interned for the lifetime of the
+    /// process, since `Value` only holds `&'static str` names.
+    ///
+    /// # Security
+    ///
+    /// Interned names are leaked and never freed, so every distinct name in the
+    /// input grows memory permanently. Don't decode untrusted input with this
+    /// function: an attacker can exhaust memory by sending bytes with many
+    /// unique names.
+    pub fn from_bytes(bs: &[u8]) -> Result<Value, Error> {
+        let mut decoder = Decoder { bs, depth: 0 };
+        let v = decoder.value()?;
+        if !decoder.bs.is_empty() {
+            return Err(Error(anyhow!(
+                "decode value: {} trailing bytes",
+                decoder.bs.len()
+            )));
+        }
+        Ok(v)
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.push(tag(self));
+        self.encode_content(buf);
+    }
+
+    /// Encode value without its tag.
+    fn encode_content(&self, buf: &mut Vec<u8>) {
+        match self {
+            Value::Bool(v) => buf.push(*v as u8),
+            Value::I8(v) => buf.extend_from_slice(&v.to_le_bytes()),
+            Value::I16(v) => buf.extend_from_slice(&v.to_le_bytes()),
+            Value::I32(v) => buf.extend_from_slice(&v.to_le_bytes()),
+            Value::I64(v) => buf.extend_from_slice(&v.to_le_bytes()),
+            Value::I128(v) => buf.extend_from_slice(&v.to_le_bytes()),
+            Value::U8(v) => buf.push(*v),
+            Value::U16(v) => buf.extend_from_slice(&v.to_le_bytes()),
+            Value::U32(v) => buf.extend_from_slice(&v.to_le_bytes()),
+            Value::U64(v) => buf.extend_from_slice(&v.to_le_bytes()),
+            Value::U128(v) => buf.extend_from_slice(&v.to_le_bytes()),
+            Value::F32(v) => buf.extend_from_slice(&v.to_le_bytes()),
+            Value::F64(v) => buf.extend_from_slice(&v.to_le_bytes()),
+            Value::Char(v) => buf.extend_from_slice(&(*v as u32).to_le_bytes()),
+            Value::Str(v) => encode_bytes(v.as_bytes(), buf),
+            Value::Bytes(v) => encode_bytes(v, buf),
+            Value::None | Value::Unit => {}
+            // Shared values are encoded as their inner value, `tag` already
+            // looks through them.
+            Value::Shared(v) => v.encode_content(buf),
+            Value::Some(v) => v.encode(buf),
+            Value::UnitStruct(name) => encode_bytes(name.as_bytes(), buf),
+            Value::UnitVariant {
+                name,
+                variant_index,
+                variant,
+            } => encode_variant(name, *variant_index, variant, buf),
+            Value::NewtypeStruct(name, v) => {
+                encode_bytes(name.as_bytes(), buf);
+                v.encode(buf);
+            }
+            Value::NewtypeVariant {
+                name,
+                variant_index,
+                variant,
+                value,
+            } => {
+                encode_variant(name, *variant_index, variant, buf);
+                value.encode(buf);
+            }
+            Value::Seq(v) | Value::Tuple(v) => encode_seq(v, buf),
+            Value::TupleStruct(name, v) => {
+                encode_bytes(name.as_bytes(), buf);
+                encode_seq(v, buf);
+            }
+            Value::TupleVariant {
+                name,
+                variant_index,
+                variant,
+                fields,
+            } => {
+                encode_variant(name, *variant_index, variant, buf);
+                encode_seq(fields, buf);
+            }
+            Value::Map(m) => {
+                encode_len(m.len(), buf);
+                for (k, v) in m {
+                    k.encode(buf);
+                    v.encode(buf);
+                }
+            }
+            Value::Struct(name, fields) => {
+                encode_bytes(name.as_bytes(), buf);
+                encode_fields(fields, buf);
+            }
+            Value::StructVariant {
+                name,
+                variant_index,
+                variant,
+                fields,
+            } => {
+                encode_variant(name, *variant_index, variant, buf);
+                encode_fields(fields, buf);
+            }
+        }
+    }
+}
+
+/// Tag byte of every variant, in the order they are declared.
+fn tag(v: &Value) -> u8 {
+    match v {
+        Value::Bool(_) => 0,
+        Value::I8(_) => 1,
+        Value::I16(_) => 2,
+        Value::I32(_) => 3,
+        Value::I64(_) => 4,
+        Value::I128(_) => 5,
+        Value::U8(_) => 6,
+        Value::U16(_) => 7,
+        Value::U32(_) => 8,
+        Value::U64(_) => 9,
+        Value::U128(_) => 10,
+        Value::F32(_) => 11,
+        Value::F64(_) => 12,
+        Value::Char(_) => 13,
+        Value::Str(_) => 14,
+        Value::Bytes(_) => 15,
+        Value::None => 16,
+        Value::Some(_) => 17,
+        Value::Unit => 18,
+        Value::UnitStruct(_) => 19,
+        Value::UnitVariant { .. } => 20,
+        Value::NewtypeStruct(_, _) => 21,
+        Value::NewtypeVariant { .. } => 22,
+        Value::Seq(_) => 23,
+        Value::Tuple(_) => 24,
+        Value::TupleStruct(_, _) => 25,
+        Value::TupleVariant { .. } => 26,
+        Value::Map(_) => 27,
+        Value::Struct(_, _) => 28,
+        Value::StructVariant { .. } => 29,
+        Value::Shared(v) => tag(v),
+    }
+}
+
+fn encode_len(mut len: usize, buf: &mut Vec<u8>) {
+    while len >= 0x80 {
+        buf.push((len as u8) | 0x80);
+        len >>= 7;
+    }
+    buf.push(len as u8);
+}
+
+fn encode_bytes(bs: &[u8], buf: &mut Vec<u8>) {
+    encode_len(bs.len(), buf);
+    buf.extend_from_slice(bs);
+}
+
+fn encode_variant(name: &str, variant_index: u32, variant: &str, buf: &mut Vec<u8>) {
+    encode_bytes(name.as_bytes(), buf);
+    buf.extend_from_slice(&variant_index.to_le_bytes());
+    encode_bytes(variant.as_bytes(), buf);
+}
+
+fn encode_seq(v: &[Value], buf: &mut Vec<u8>) {
+    encode_len(v.len(), buf);
+    for v in v {
+        v.encode(buf);
+    }
+}
+
+fn encode_fields(fields: &IndexMap<&'static str, Value>, buf: &mut Vec<u8>) {
+    encode_len(fields.len(), buf);
+    for (k, v) in fields {
+        encode_bytes(k.as_bytes(), buf);
+        v.encode(buf);
+    }
+}
+
+struct Decoder<'a> {
+    bs: &'a [u8],
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        if self.bs.len() < N {
+            return Err(Error(anyhow!("decode value: unexpected end of input")));
+        }
+        let (head, rest) = self.bs.split_at(N);
+        self.bs = rest;
+        Ok(head.try_into().expect("must have N bytes"))
+    }
+
+    fn len(&mut self) -> Result<usize, Error> {
+        let mut len: usize = 0;
+        for shift in (0..usize::BITS).step_by(7) {
+            let [b] = self.take::<1>()?;
+            len |= ((b & 0x7f) as usize)
+                .checked_shl(shift)
+                .filter(|v| v >> shift == (b & 0x7f) as usize)
+                .ok_or_else(|| Error(anyhow!("decode value: length overflow")))?;
+            if b & 0x80 == 0 {
+                return Ok(len);
+            }
+        }
+        Err(Error(anyhow!("decode value: length overflow")))
+    }
+
+    fn bytes(&mut self) -> Result<Vec<u8>, Error> {
+        let len = self.len()?;
+        if self.bs.len() < len {
+            return Err(Error(anyhow!("decode value: unexpected end of input")));
+        }
+        let (head, rest) = self.bs.split_at(len);
+        self.bs = rest;
+        Ok(head.to_vec())
+    }
+
+    fn string(&mut self) -> Result<String, Error> {
+        String::from_utf8(self.bytes()?)
+            .map_err(|e| Error(anyhow!("decode value: invalid utf-8: {}", e)))
+    }
+
+    fn name(&mut self) -> Result<&'static str, Error> {
+        Ok(intern(&self.string()?))
+    }
+
+    fn variant(&mut self) -> Result<(&'static str, u32, &'static str), Error> {
+        let name = self.name()?;
+        let variant_index = u32::from_le_bytes(self.take()?);
+        let variant = self.name()?;
+        Ok((name, variant_index, variant))
+    }
+
+    fn seq(&mut self) -> Result<Vec<Value>, Error> {
+        let len = self.len()?;
+        // Every value takes at least one byte, don't trust `len` for allocation.
+        let mut v = Vec::with_capacity(len.min(self.bs.len()));
+        for _ in 0..len {
+            v.push(self.value()?);
+        }
+        Ok(v)
+    }
+
+    fn fields(&mut self) -> Result<IndexMap<&'static str, Value>, Error> {
+        let len = self.len()?;
+        let mut fields = IndexMap::with_capacity(len.min(self.bs.len()));
+        for _ in 0..len {
+            let k = self.name()?;
+            fields.insert(k, self.value()?);
+        }
+        Ok(fields)
+    }
+
+    fn value(&mut self) -> Result<Value, Error> {
+        if self.depth == MAX_DEPTH {
+            return Err(Error(anyhow!("decode value: recursion limit exceeded")));
+        }
+        self.depth += 1;
+        let v = self.value_inner();
+        self.depth -= 1;
+        v
+    }
+
+    fn value_inner(&mut self) -> Result<Value, Error> {
+        let [tag] = self.take::<1>()?;
+        let v = match tag {
+            0 => match self.take::<1>()? {
+                [0] => Value::Bool(false),
+                [1] => Value::Bool(true),
+                [b] => return Err(Error(anyhow!("decode value: invalid bool {}", b))),
+            },
+            1 => Value::I8(i8::from_le_bytes(self.take()?)),
+            2 => Value::I16(i16::from_le_bytes(self.take()?)),
+            3 => Value::I32(i32::from_le_bytes(self.take()?)),
+            4 => Value::I64(i64::from_le_bytes(self.take()?)),
+            5 => Value::I128(i128::from_le_bytes(self.take()?)),
+            6 => Value::U8(u8::from_le_bytes(self.take()?)),
+            7 => Value::U16(u16::from_le_bytes(self.take()?)),
+            8 => Value::U32(u32::from_le_bytes(self.take()?)),
+            9 => Value::U64(u64::from_le_bytes(self.take()?)),
+            10 => Value::U128(u128::from_le_bytes(self.take()?)),
+            11 => Value::F32(f32::from_le_bytes(self.take()?)),
+            12 => Value::F64(f64::from_le_bytes(self.take()?)),
+            13 => {
+                let c = u32::from_le_bytes(self.take()?);
+                Value::Char(
+                    char::from_u32(c)
+                        .ok_or_else(|| Error(anyhow!("decode value: invalid char {}", c)))?,
+                )
+            }
+            14 => Value::Str(self.string()?),
+            15 => Value::Bytes(self.bytes()?),
+            16 => Value::None,
+            17 => Value::Some(Box::new(self.value()?)),
+            18 => Value::Unit,
+            19 => Value::UnitStruct(self.name()?),
+            20 => {
+                let (name, variant_index, variant) = self.variant()?;
+                Value::UnitVariant {
+                    name,
+                    variant_index,
+                    variant,
+                }
+            }
+            21 => Value::NewtypeStruct(self.name()?, Box::new(self.value()?)),
+            22 => {
+                let (name, variant_index, variant) = self.variant()?;
+                Value::NewtypeVariant {
+                    name,
+                    variant_index,
+                    variant,
+                    value: Box::new(self.value()?),
+                }
+            }
+            23 => Value::Seq(self.seq()?),
+            24 => Value::Tuple(self.seq()?),
+            25 => Value::TupleStruct(self.name()?, self.seq()?),
+            26 => {
+                let (name, variant_index, variant) = self.variant()?;
+                Value::TupleVariant {
+                    name,
+                    variant_index,
+                    variant,
+                    fields: self.seq()?,
+                }
+            }
+            27 => {
+                let len = self.len()?;
+                let mut m = IndexMap::with_capacity(len.min(self.bs.len()));
+                for _ in 0..len {
+                    let k = self.value()?;
+                    m.insert(k, self.value()?);
+                }
+                Value::Map(m)
+            }
+            28 => Value::Struct(self.name()?, self.fields()?),
+            29 => {
+                let (name, variant_index, variant) = self.variant()?;
+                Value::StructVariant {
+                    name,
+                    variant_index,
+                    variant,
+                    fields: self.fields()?,
+                }
+            }
+            tag => return Err(Error(anyhow!("decode value: invalid tag {}", tag))),
+        };
+        Ok(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_round_trip_all_variants() -> anyhow::Result<()> {
+        let mut variants = crate::fixtures::all_variants();
+        variants.extend([
+            Value::I128(i128::MIN),
+            Value::U128(u128::MAX),
+            Value::F64(-2.5),
+            Value::Char('中'),
+            Value::Str("s".repeat(200)),
+            Value::Tuple(vec![Value::U8(1), Value::U16(2)]),
+            Value::Map(indexmap! { Value::I32(1) => Value::U8(1) }),
+        ]);
+        let v = Value::Seq(variants);
+
+        let bs = v.to_bytes();
+        let decoded = Value::from_bytes(&bs)?;
+        assert_eq!(decoded, v);
+        // `PartialEq` for floats and maps is loose, compare encodings as well.
+        assert_eq!(decoded.to_bytes(), bs);
+        assert!(matches!(
+            decoded.pointer("/27"),
+            Some(Value::Struct("S", _))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared() -> anyhow::Result<()> {
+        let inner = Value::Seq(vec![Value::Str("a".to_string()), Value::U8(1)]);
+        let v = Value::Some(Box::new(inner.clone().shared()));
+
+        assert_eq!(
+            v.to_bytes(),
+            Value::Some(Box::new(inner.clone())).to_bytes()
+        );
+        assert_eq!(
+            Value::from_bytes(&v.to_bytes())?,
+            Value::Some(Box::new(inner))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_invalid_bytes() {
+        let bs = Value::Str("hello".to_string()).to_bytes();
+        assert_eq!(
+            Value::from_bytes(&bs[..3]).unwrap_err().to_string(),
+            "decode value: unexpected end of input"
+        );
+        assert!(Value::from_bytes(&[bs.as_slice(), &[0]].concat()).is_err());
+        assert!(Value::from_bytes(&[30]).is_err());
+        assert!(Value::from_bytes(&[23, 0xff, 0xff, 0xff, 0xff, 0x0f]).is_err());
+        assert!(Value::from_bytes(&[17; 1000]).is_err());
+    }
+}
@@ -0,0 +1,563 @@
+use std::cmp::Ordering;
+
+use indexmap::IndexMap;
+
+use crate::Value;
+
+/// Implement a total order for Value so that it can be sorted and used as `BTreeMap` key.
+///
+/// ## Notes
+///
+/// - Values of different variants are ordered by the declaration order of variants,
+///   except that all numbers are grouped together and compared by their value
+///   across widths, for example `I8(2) > U64(1) > F32(0.5)`. Numbers with the same
+///   value are then ordered by variant, so `I32(1) < I64(1)`.
+/// - Floats use [`f64::total_cmp`] unless they are equal, so `-0.0 == 0.0` and
+///   `NaN` is ordered after every other number. Same as `PartialEq`, the order of
+///   `NaN` with itself is not consistent with equality.
+/// - Maps and structs are compared by entries sorted by key, since their equality
+///   doesn't depend on the insertion order.
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        use Value::*;
+
+        match (self, other) {
+            (a, b) if a.rank() != b.rank() => a.rank().cmp(&b.rank()),
+            (Bool(a), Bool(b)) => a.cmp(b),
+            (Char(a), Char(b)) => a.cmp(b),
+            (Str(a), Str(b)) => a.cmp(b),
+            (Bytes(a), Bytes(b)) => a.cmp(b),
+            (Some(a), Some(b)) => a.cmp(b),
+            (UnitStruct(a), UnitStruct(b)) => a.cmp(b),
+            (
+                UnitVariant {
+                    name: an,
+                    variant_index: ai,
+                    variant: av,
+                },
+                UnitVariant {
+                    name: bn,
+                    variant_index: bi,
+                    variant: bv,
+                },
+            ) => (an, ai, av).cmp(&(bn, bi, bv)),
+            (NewtypeStruct(an, a), NewtypeStruct(bn, b)) => (an, a).cmp(&(bn, b)),
+            (
+                NewtypeVariant {
+                    name: an,
+                    variant_index: ai,
+                    variant: av,
+                    value: a,
+                },
+                NewtypeVariant {
+                    name: bn,
+                    variant_index: bi,
+                    variant: bv,
+                    value: b,
+                },
+            ) => (an, ai, av, a).cmp(&(bn, bi, bv, b)),
+            (Seq(a), Seq(b)) | (Tuple(a), Tuple(b)) => a.cmp(b),
+            (TupleStruct(an, a), TupleStruct(bn, b)) => (an, a).cmp(&(bn, b)),
+            (
+                TupleVariant {
+                    name: an,
+                    variant_index: ai,
+                    variant: av,
+                    fields: a,
+                },
+                TupleVariant {
+                    name: bn,
+                    variant_index: bi,
+                    variant: bv,
+                    fields: b,
+                },
+            ) => (an, ai, av, a).cmp(&(bn, bi, bv, b)),
+            (Map(a), Map(b)) => cmp_entries(a, b),
+            (Shared(a), Shared(b)) => a.cmp(b),
+            (Struct(an, a), Struct(bn, b)) => an.cmp(bn).then_with(|| cmp_entries(a, b)),
+            (
+                StructVariant {
+                    name: an,
+                    variant_index: ai,
+                    variant: av,
+                    fields: a,
+                },
+                StructVariant {
+                    name: bn,
+                    variant_index: bi,
+                    variant: bv,
+                    fields: b,
+                },
+            ) => (an, ai, av)
+                .cmp(&(bn, bi, bv))
+                .then_with(|| cmp_entries(a, b)),
+            (a, b) => match (a.number(), b.number()) {
+                (Option::Some(x), Option::Some(y)) => x
+                    .cmp(&y)
+                    .then_with(|| a.number_rank().cmp(&b.number_rank())),
+                // Both are `None` or `Unit`.
+                _ => Ordering::Equal,
+            },
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn cmp_entries<K: Ord>(a: &IndexMap<K, Value>, b: &IndexMap<K, Value>) -> Ordering {
+    let mut a: Vec<_> = a.iter().collect();
+    let mut b: Vec<_> = b.iter().collect();
+    a.sort_by(|x, y| x.0.cmp(y.0));
+    b.sort_by(|x, y| x.0.cmp(y.0));
+    a.cmp(&b)
+}
+
+impl Value {
+    /// Rank of variants while comparing, all numbers share the same rank.
+    fn rank(&self) -> u8 {
+        match self {
+            Value::Bool(_) => 0,
+            Value::I8(_)
+            | Value::I16(_)
+            | Value::I32(_)
+            | Value::I64(_)
+            | Value::I128(_)
+            | Value::U8(_)
+            | Value::U16(_)
+            | Value::U32(_)
+            | Value::U64(_)
+            | Value::U128(_)
+            | Value::F32(_)
+            | Value::F64(_) => 1,
+            Value::Char(_) => 2,
+            Value::Str(_) => 3,
+            Value::Bytes(_) => 4,
+            Value::None => 5,
+            Value::Some(_) => 6,
+            Value::Unit => 7,
+            Value::UnitStruct(_) => 8,
+            Value::UnitVariant { .. } => 9,
+            Value::NewtypeStruct(..) => 10,
+            Value::NewtypeVariant { .. } => 11,
+            Value::Seq(_) => 12,
+            Value::Tuple(_) => 13,
+            Value::TupleStruct(..) => 14,
+            Value::TupleVariant { .. } => 15,
+            Value::Map(_) => 16,
+            Value::Struct(..) => 17,
+            Value::StructVariant { .. } => 18,
+            Value::Shared(_) => 19,
+        }
+    }
+
+    /// Rank of number variants, used to order numbers with the same value.
+    fn number_rank(&self) -> u8 {
+        match self {
+            Value::I8(_) => 0,
+            Value::I16(_) => 1,
+            Value::I32(_) => 2,
+            Value::I64(_) => 3,
+            Value::I128(_) => 4,
+            Value::U8(_) => 5,
+            Value::U16(_) => 6,
+            Value::U32(_) => 7,
+            Value::U64(_) => 8,
+            Value::U128(_) => 9,
+            Value::F32(_) => 10,
+            _ => 11,
+        }
+    }
+
+    fn number(&self) -> Option<Number> {
+        let n = match self {
+            Value::I8(v) => Number::Int(*v as i128),
+            Value::I16(v) => Number::Int(*v as i128),
+            Value::I32(v) => Number::Int(*v as i128),
+            Value::I64(v) => Number::Int(*v as i128),
+            Value::I128(v) => Number::Int(*v),
+            Value::U8(v) => Number::Int(*v as i128),
+            Value::U16(v) => Number::Int(*v as i128),
+            Value::U32(v) => Number::Int(*v as i128),
+            Value::U64(v) => Number::Int(*v as i128),
+            Value::U128(v) => Number::UInt(*v),
+            Value::F32(v) => Number::Float(*v as f64),
+            Value::F64(v) => Number::Float(*v),
+            _ => return None,
+        };
+        Some(n)
+    }
+}
+
+/// A number widened for comparing across variants.
+#[derive(Clone, Copy)]
+enum Number {
+    Int(i128),
+    /// Only used for `u128` values that may not fit in `i128`.
+    UInt(u128),
+    Float(f64),
+}
+
+impl PartialEq for Number {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Number {}
+
+impl PartialOrd for Number {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Number {
+    fn cmp(&self, other: &Self) -> Ordering {
+        use Number::*;
+
+        match (*self, *other) {
+            (Int(a), Int(b)) => a.cmp(&b),
+            (UInt(a), UInt(b)) => a.cmp(&b),
+            (Int(a), UInt(b)) => match u128::try_from(a) {
+                Ok(a) => a.cmp(&b),
+                Err(_) => Ordering::Less,
+            },
+            (Float(a), Float(b)) => match (a.is_nan(), b.is_nan()) {
+                (false, false) => a.partial_cmp(&b).expect("not NaN"),
+                (true, true) => a.total_cmp(&b),
+                (a, b) => a.cmp(&b),
+            },
+            (Float(_), _) | (UInt(_), Int(_)) => other.cmp(self).reverse(),
+            (n, Float(f)) => n.cmp_float(f),
+        }
+    }
+}
+
+impl Number {
+    /// Compare an integer with a float without losing precision.
+    fn cmp_float(self, f: f64) -> Ordering {
+        // 2^128 is exactly representable in f64.
+        const U128_BOUND: f64 = 340_282_366_920_938_463_463_374_607_431_768_211_456.0;
+
+        if f.is_nan() {
+            return Ordering::Less;
+        }
+        let n = match self {
+            Number::Int(v) => v as f64,
+            Number::UInt(v) => v as f64,
+            Number::Float(_) => unreachable!("must be integer"),
+        };
+        // Rounding to float is monotonic, so distinct floats keep the order.
+        if n != f {
+            return n.partial_cmp(&f).expect("not NaN");
+        }
+        // `f` is integral now, compare them as integers.
+        if f >= U128_BOUND {
+            Ordering::Less
+        } else if f >= 0.0 {
+            self.cmp(&Number::UInt(f as u128))
+        } else {
+            self.cmp(&Number::Int(f as i128))
+        }
+    }
+}
+
+impl Value {
+    /// Check whether two values represent the same document in name-erased
+    /// formats like JSON.
+    ///
+    /// - Numbers are compared by their value across widths, so `I32(1)` equals
+    ///   `U64(1)` and `F64(1.0)`.
+    /// - `Shared` is transparent.
+    /// - Struct names are ignored: `NewtypeStruct` is transparent, `UnitStruct`
+    ///   equals `Unit`, `TupleStruct` equals `Seq` and `Tuple`.
+    /// - `Struct` equals `Map` with the same fields as `Value::Str` keys, the order
+    ///   of entries is ignored. Keys of two maps are compared exactly.
+    /// - Enum names and variant indices are ignored, but variants must have the
+    ///   same variant name and payload.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::indexmap;
+    /// use serde_bridge::Value;
+    ///
+    /// let s = Value::Struct("Point", indexmap! { "x" => Value::I32(1) });
+    /// let m = Value::Map(indexmap! { Value::Str("x".to_string()) => Value::I64(1) });
+    /// assert!(s.structural_eq(&m));
+    /// ```
+    pub fn structural_eq(&self, other: &Value) -> bool {
+        use Value::*;
+
+        match (self, other) {
+            (NewtypeStruct(_, a), b) | (b, NewtypeStruct(_, a)) => a.structural_eq(b),
+            (Shared(a), b) | (b, Shared(a)) => a.structural_eq(b),
+            (Some(a), Some(b)) => a.structural_eq(b),
+            (Unit | UnitStruct(_), Unit | UnitStruct(_)) => true,
+            (Seq(a) | Tuple(a) | TupleStruct(_, a), Seq(b) | Tuple(b) | TupleStruct(_, b)) => {
+                structural_eq_elements(a, b)
+            }
+            (Map(_) | Struct(..), Map(_) | Struct(..)) => {
+                structural_eq_entries(Entries::of(self), Entries::of(other))
+            }
+            (UnitVariant { variant: av, .. }, UnitVariant { variant: bv, .. }) => av == bv,
+            (
+                NewtypeVariant {
+                    variant: av,
+                    value: a,
+                    ..
+                },
+                NewtypeVariant {
+                    variant: bv,
+                    value: b,
+                    ..
+                },
+            ) => av == bv && a.structural_eq(b),
+            (
+                TupleVariant {
+                    variant: av,
+                    fields: a,
+                    ..
+                },
+                TupleVariant {
+                    variant: bv,
+                    fields: b,
+                    ..
+                },
+            ) => av == bv && structural_eq_elements(a, b),
+            (
+                StructVariant {
+                    variant: av,
+                    fields: a,
+                    ..
+                },
+                StructVariant {
+                    variant: bv,
+                    fields: b,
+                    ..
+                },
+            ) => av == bv && structural_eq_entries(Entries::Fields(a), Entries::Fields(b)),
+            (a, b) => match (a.number(), b.number()) {
+                (Option::Some(x), Option::Some(y)) => x == y,
+                (Option::None, Option::None) => a == b,
+                _ => false,
+            },
+        }
+    }
+}
+
+fn structural_eq_elements(a: &[Value], b: &[Value]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.structural_eq(b))
+}
+
+fn structural_eq_entries(a: Entries, b: Entries) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    match a {
+        Entries::Map(m) => m
+            .iter()
+            .all(|(k, v)| b.get_value(k).is_some_and(|bv| v.structural_eq(bv))),
+        Entries::Fields(f) => f
+            .iter()
+            .all(|(k, v)| b.get_field(k).is_some_and(|bv| v.structural_eq(bv))),
+    }
+}
+
+/// Entries of a `Map` or fields of a `Struct`, whose keys are seen as `Value::Str`.
+#[derive(Clone, Copy)]
+enum Entries<'a> {
+    Map(&'a IndexMap<Value, Value>),
+    Fields(&'a IndexMap<&'static str, Value>),
+}
+
+impl<'a> Entries<'a> {
+    fn of(v: &'a Value) -> Self {
+        match v {
+            Value::Map(m) => Entries::Map(m),
+            Value::Struct(_, f) => Entries::Fields(f),
+            _ => unreachable!("must be map or struct"),
+        }
+    }
+
+    fn len(self) -> usize {
+        match self {
+            Entries::Map(m) => m.len(),
+            Entries::Fields(f) => f.len(),
+        }
+    }
+
+    fn get_value(self, key: &Value) -> Option<&'a Value> {
+        match (self, key) {
+            (Entries::Map(m), key) => m.get(key),
+            (Entries::Fields(f), Value::Str(key)) => f.get(key.as_str()),
+            (Entries::Fields(_), _) => None,
+        }
+    }
+
+    fn get_field(self, key: &str) -> Option<&'a Value> {
+        match self {
+            Entries::Map(m) => m.get(&Value::Str(key.to_string())),
+            Entries::Fields(f) => f.get(key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_sort() {
+        let mut v = vec![
+            Value::Str("b".to_string()),
+            Value::F64(f64::NAN),
+            Value::I64(1),
+            Value::Seq(vec![Value::I8(1)]),
+            Value::U8(2),
+            Value::None,
+            Value::F32(0.5),
+            Value::I32(1),
+            Value::Str("a".to_string()),
+            Value::I128(-3),
+            Value::Bool(true),
+            Value::U128(u128::MAX),
+            Value::F64(f64::NEG_INFINITY),
+        ];
+        v.sort();
+
+        assert_eq!(v[0], Value::Bool(true));
+        assert_eq!(v[1], Value::F64(f64::NEG_INFINITY));
+        assert_eq!(
+            v[2..8],
+            [
+                Value::I128(-3),
+                Value::F32(0.5),
+                Value::I32(1),
+                Value::I64(1),
+                Value::U8(2),
+                Value::U128(u128::MAX),
+            ]
+        );
+        assert!(matches!(v[8], Value::F64(f) if f.is_nan()));
+        assert_eq!(
+            v[9..],
+            [
+                Value::Str("a".to_string()),
+                Value::Str("b".to_string()),
+                Value::None,
+                Value::Seq(vec![Value::I8(1)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_number_cmp() {
+        assert!(Value::U128(u128::MAX) < Value::F64(2f64.powi(128)));
+        assert!(Value::I64(i64::MAX) < Value::F64(2f64.powi(63)));
+        assert!(Value::I64(-1) < Value::U128(0));
+        assert_eq!(Value::F32(-0.0).cmp(&Value::F32(0.0)), Ordering::Equal);
+        assert!(Value::F64(1.5) > Value::I8(1));
+    }
+
+    #[test]
+    fn test_map_cmp() {
+        let a = Value::Map(indexmap! {
+            Value::Str("a".to_string()) => Value::I32(1),
+            Value::Str("b".to_string()) => Value::I32(2),
+        });
+        let b = Value::Map(indexmap! {
+            Value::Str("b".to_string()) => Value::I32(2),
+            Value::Str("a".to_string()) => Value::I32(1),
+        });
+        assert_eq!(a, b);
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_btree_map_key() {
+        let mut m = BTreeMap::new();
+        m.insert(Value::Str("x".to_string()), 1);
+        m.insert(Value::I64(10), 2);
+        m.insert(Value::U8(3), 3);
+        m.insert(Value::I64(10), 4);
+
+        assert_eq!(m.len(), 3);
+        assert_eq!(m.get(&Value::I64(10)), Some(&4));
+        assert_eq!(m.values().collect::<Vec<_>>(), vec![&3, &4, &1]);
+    }
+
+    #[test]
+    fn test_structural_eq() {
+        let s = Value::Struct(
+            "Server",
+            indexmap! {
+                "host" => Value::Str("localhost".to_string()),
+                "port" => Value::U16(8080),
+                "tags" => Value::TupleStruct("Tags", vec![Value::Str("a".to_string())]),
+            },
+        );
+        let m = Value::Map(indexmap! {
+            Value::Str("port".to_string()) => Value::I64(8080),
+            Value::Str("tags".to_string()) => Value::Seq(vec![Value::Str("a".to_string())]),
+            Value::Str("host".to_string()) => Value::Str("localhost".to_string()),
+        });
+        assert!(s.structural_eq(&m));
+        assert!(m.structural_eq(&s));
+        assert_ne!(s, m);
+
+        let renamed = Value::NewtypeStruct(
+            "Wrapper",
+            Box::new(Value::Struct(
+                "Other",
+                indexmap! {
+                    "host" => Value::Str("localhost".to_string()),
+                    "port" => Value::F64(8080.0),
+                    "tags" => Value::Tuple(vec![Value::Str("a".to_string())]),
+                },
+            )),
+        );
+        assert!(s.structural_eq(&renamed));
+
+        let mut other = m.clone();
+        other
+            .set_path("/port", Value::I64(80))
+            .expect("set must success");
+        assert!(!s.structural_eq(&other));
+
+        let m = Value::Map(indexmap! { Value::I32(1) => Value::Bool(true) });
+        assert!(!m.structural_eq(&Value::Struct("S", indexmap! { "1" => Value::Bool(true) })));
+    }
+
+    #[test]
+    fn test_structural_eq_variants() {
+        let a = Value::UnitVariant {
+            name: "A",
+            variant_index: 0,
+            variant: "X",
+        };
+        let b = Value::UnitVariant {
+            name: "B",
+            variant_index: 1,
+            variant: "X",
+        };
+        let c = Value::UnitVariant {
+            name: "A",
+            variant_index: 1,
+            variant: "Y",
+        };
+        assert!(a.structural_eq(&b));
+        assert!(!a.structural_eq(&c));
+        assert!(Value::UnitStruct("U").structural_eq(&Value::Unit));
+        assert!(!Value::Unit.structural_eq(&Value::None));
+        assert!(!Value::Str("1".to_string()).structural_eq(&Value::I32(1)));
+    }
+}
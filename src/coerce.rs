@@ -0,0 +1,171 @@
+use anyhow::anyhow;
+
+use crate::{from_value_with_config, Config, Error, Value, ValueKind};
+
+impl Value {
+    /// Convert value into the variant of given [`ValueKind`] where sensible.
+    ///
+    /// Scalars follow the rules of [`Config::lenient`], plus:
+    ///
+    /// - `Bool`, `Char` and numbers can be converted into `Str`.
+    /// - `Bool` can be converted into numbers as `0` and `1`.
+    /// - Integers can be converted into floats, rounding if they can't be
+    ///   represented exactly.
+    /// - Floats are narrowed into `F32` like `as` does.
+    ///
+    /// Values of the requested kind are returned as is. Other conversions, including
+    /// every container kind, return an error. `Value::Shared` is unwrapped first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{Value, ValueKind};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// assert_eq!(Value::Str("42".to_string()).coerce_to(ValueKind::I64)?, Value::I64(42));
+    /// assert_eq!(Value::I64(42).coerce_to(ValueKind::Str)?, Value::Str("42".to_string()));
+    /// assert!(Value::Str("abc".to_string()).coerce_to(ValueKind::I64).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn coerce_to(self, kind: ValueKind) -> Result<Value, Error> {
+        let value = self.into_unshared();
+        let from = value.kind();
+        if from == kind {
+            return Ok(value);
+        }
+
+        match (kind, value) {
+            (ValueKind::Str, v) => v
+                .scalar_to_string()
+                .map(Value::Str)
+                .ok_or_else(|| Error(anyhow!("cannot coerce {} into {}", from, kind))),
+            (k, Value::Bool(b)) if k.is_integer() || k.is_float() => {
+                Value::U8(b as u8).coerce_to(kind)
+            }
+            (k, v) if k.is_float() && from.is_integer() => {
+                let f = v.to_f64_lossy().expect("must be integer");
+                Value::F64(f).coerce_to(kind)
+            }
+            (k, v)
+                if k.is_integer()
+                    || k.is_float()
+                    || matches!(k, ValueKind::Bool | ValueKind::Char) =>
+            {
+                v.deserialize_as(k)
+                    .map_err(|e| e.context(format!("coerce {} into {}", from, kind)))
+            }
+            _ => Err(Error(anyhow!("cannot coerce {} into {}", from, kind))),
+        }
+    }
+
+    /// Deserialize into the scalar type of `kind` in lenient mode.
+    fn deserialize_as(self, kind: ValueKind) -> Result<Value, Error> {
+        let c = Config::new().lenient(true);
+        let v = match kind {
+            ValueKind::Bool => Value::Bool(from_value_with_config(self, c)?),
+            ValueKind::I8 => Value::I8(from_value_with_config(self, c)?),
+            ValueKind::I16 => Value::I16(from_value_with_config(self, c)?),
+            ValueKind::I32 => Value::I32(from_value_with_config(self, c)?),
+            ValueKind::I64 => Value::I64(from_value_with_config(self, c)?),
+            ValueKind::I128 => Value::I128(from_value_with_config(self, c)?),
+            ValueKind::U8 => Value::U8(from_value_with_config(self, c)?),
+            ValueKind::U16 => Value::U16(from_value_with_config(self, c)?),
+            ValueKind::U32 => Value::U32(from_value_with_config(self, c)?),
+            ValueKind::U64 => Value::U64(from_value_with_config(self, c)?),
+            ValueKind::U128 => Value::U128(from_value_with_config(self, c)?),
+            ValueKind::F32 => Value::F32(from_value_with_config(self, c)?),
+            ValueKind::F64 => Value::F64(from_value_with_config(self, c)?),
+            ValueKind::Char => Value::Char(from_value_with_config(self, c)?),
+            _ => unreachable!("must be scalar kind"),
+        };
+        Ok(v)
+    }
+
+    fn scalar_to_string(&self) -> Option<String> {
+        let s = match self.unshared() {
+            Value::Bool(v) => v.to_string(),
+            Value::I8(v) => v.to_string(),
+            Value::I16(v) => v.to_string(),
+            Value::I32(v) => v.to_string(),
+            Value::I64(v) => v.to_string(),
+            Value::I128(v) => v.to_string(),
+            Value::U8(v) => v.to_string(),
+            Value::U16(v) => v.to_string(),
+            Value::U32(v) => v.to_string(),
+            Value::U64(v) => v.to_string(),
+            Value::U128(v) => v.to_string(),
+            Value::F32(v) => v.to_string(),
+            Value::F64(v) => v.to_string(),
+            Value::Char(v) => v.to_string(),
+            _ => return None,
+        };
+        Some(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::*;
+
+    #[test]
+    fn test_coerce_to() -> Result<()> {
+        let v = Value::Str("42".to_string()).coerce_to(ValueKind::I64)?;
+        assert_eq!(v, Value::I64(42));
+        let v = Value::I64(42).coerce_to(ValueKind::Str)?;
+        assert_eq!(v, Value::Str("42".to_string()));
+
+        assert_eq!(Value::I32(3).coerce_to(ValueKind::F64)?, Value::F64(3.0));
+        assert_eq!(Value::Bool(true).coerce_to(ValueKind::I64)?, Value::I64(1));
+        assert_eq!(Value::F64(4.0).coerce_to(ValueKind::U8)?, Value::U8(4));
+        assert_eq!(Value::U32(97).coerce_to(ValueKind::Char)?, Value::Char('a'));
+        assert_eq!(
+            Value::Str("true".to_string()).coerce_to(ValueKind::Bool)?,
+            Value::Bool(true)
+        );
+        assert_eq!(
+            Value::F64(1.5).coerce_to(ValueKind::Str)?,
+            Value::Str("1.5".to_string())
+        );
+
+        // Shared values are unwrapped first.
+        assert_eq!(
+            Value::I64(42).shared().coerce_to(ValueKind::Str)?,
+            Value::Str("42".to_string())
+        );
+        assert_eq!(
+            Value::I32(3).shared().coerce_to(ValueKind::F64)?,
+            Value::F64(3.0)
+        );
+        assert_eq!(
+            Value::U8(1).shared().coerce_to(ValueKind::U8)?,
+            Value::U8(1)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_coerce_to_error() {
+        let err = Value::Str("abc".to_string())
+            .coerce_to(ValueKind::I64)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"coerce str into i64: invalid i64: "abc""#
+        );
+
+        assert!(Value::I64(300).coerce_to(ValueKind::U8).is_err());
+        assert!(Value::F64(1.5).coerce_to(ValueKind::I64).is_err());
+        assert!(Value::Seq(vec![]).coerce_to(ValueKind::Str).is_err());
+        let err = Value::I64(1).coerce_to(ValueKind::Map).unwrap_err();
+        assert_eq!(err.to_string(), "cannot coerce i64 into map");
+        assert_eq!(
+            Value::Seq(vec![])
+                .coerce_to(ValueKind::Seq)
+                .expect("must success"),
+            Value::Seq(vec![])
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use anyhow::anyhow;
+
+use crate::Error;
+
+/// Config controls how [`Value`](crate::Value) is bridged from/to other serde formats.
+///
+/// The default config keeps the behavior of serde: every option is opt-in.
+///
+/// # Examples
+///
+/// ```
+/// use serde_bridge::Config;
+///
+/// let config = Config::default().newtype_transparent(false);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Config {
+    pub(crate) newtype_transparent: bool,
+    pub(crate) allow_short_seq: bool,
+    pub(crate) unit_as_empty_tuple: bool,
+    pub(crate) lenient: bool,
+    pub(crate) option_from_plain: bool,
+    pub(crate) deny_unknown_fields: bool,
+    /// While deserializing, this is the remaining depth instead of the limit.
+    pub(crate) max_depth: usize,
+    pub(crate) nan_handling: NanHandling,
+    pub(crate) exact_f32: bool,
+}
+
+/// How non-finite floats (`NaN`, `inf` and `-inf`) are serialized, picked by
+/// [`Config::nan_handling`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NanHandling {
+    /// Pass them to the serializer as is, which decides what to do.
+    #[default]
+    Keep,
+    /// Return an error.
+    Error,
+    /// Serialize as `None`, which is `null` in JSON.
+    Null,
+    /// Serialize as strings `NaN`, `inf` and `-inf`.
+    String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            newtype_transparent: true,
+            allow_short_seq: false,
+            unit_as_empty_tuple: false,
+            lenient: false,
+            option_from_plain: false,
+            deny_unknown_fields: false,
+            max_depth: 128,
+            nan_handling: NanHandling::Keep,
+            exact_f32: false,
+        }
+    }
+}
+
+impl Config {
+    /// Create a new config with default values.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serialize `Value::NewtypeStruct` transparently or not.
+    ///
+    /// - `true` (default): call `serialize_newtype_struct` like serde does, most
+    ///   formats (for example `serde_json`) will emit the inner value directly.
+    /// - `false`: serialize as a single entry map `{ name: inner }` so the name is kept.
+    pub fn newtype_transparent(mut self, v: bool) -> Self {
+        self.newtype_transparent = v;
+        self
+    }
+
+    /// Allow deserializing struct from a `Value::Seq` shorter than its fields.
+    ///
+    /// Structs can be deserialized from `Value::Seq`/`Value::Tuple` by position. By
+    /// default the length must match the fields exactly. When enabled, missing trailing
+    /// positions are filled with `Value::None` so `Option` fields become `None`, which
+    /// is useful for formats like CSV that omit trailing optional columns.
+    pub fn allow_short_seq(mut self, v: bool) -> Self {
+        self.allow_short_seq = v;
+        self
+    }
+
+    /// Allow deserializing zero-length tuples (like `[T; 0]`) from `Value::Unit`.
+    ///
+    /// Both `()` and `[T; 0]` contain no data, but serde drives them differently:
+    /// `()` via `deserialize_unit` and `[T; 0]` via `deserialize_tuple(0)`. By default
+    /// zero-length tuples require an empty `Value::Tuple`/`Value::Seq`.
+    pub fn unit_as_empty_tuple(mut self, v: bool) -> Self {
+        self.unit_as_empty_tuple = v;
+        self
+    }
+
+    /// Coerce scalars into the requested type while deserializing.
+    ///
+    /// Self-describing formats like CSV or query strings carry every scalar as a
+    /// string. When enabled:
+    ///
+    /// - `bool` can be parsed from `Value::Str` `true`/`false`.
+    /// - Integers can be parsed from `Value::Str` in decimal with optional sign.
+    /// - `f32`/`f64` can be parsed from `Value::Str` via `str::parse`, which accepts
+    ///   decimals (`3.14`), exponents (`1e3`), `NaN`, `inf` and `infinity` (case
+    ///   insensitive, with optional sign).
+    /// - Integers can be converted from `F32`/`F64` without fractional part if they
+    ///   fit the target type, for example `4.0` into `i64`.
+    /// - `char` can be converted from integers that are valid Unicode scalar values.
+    /// - Tuples can be built from `Value::Map` with `Value::Str` keys `"0".."n-1"`,
+    ///   elements are ordered by their index.
+    ///
+    /// Surrounding whitespace is always rejected.
+    pub fn lenient(mut self, v: bool) -> Self {
+        self.lenient = v;
+        self
+    }
+
+    /// Deserialize `Option` from values that are not `Value::None`/`Value::Some`.
+    ///
+    /// Self-describing formats like JSON have no options: `null` is read as
+    /// `Value::Unit` and present values are read as is. When enabled, `Option` is
+    /// deserialized from `Value::Unit` as `None` and from any other value as `Some`.
+    /// By default only `Value::None` and `Value::Some` are accepted.
+    pub fn option_from_plain(mut self, v: bool) -> Self {
+        self.option_from_plain = v;
+        self
+    }
+
+    /// Reject maps and structs with fields unknown to the target struct.
+    ///
+    /// By default unknown fields are ignored like serde does. When enabled,
+    /// deserializing a struct or struct variant from a `Value::Struct`,
+    /// `Value::StructVariant` or `Value::Map` whose keys are not in the target's
+    /// fields returns an error, like `#[serde(deny_unknown_fields)]` on every struct.
+    ///
+    /// Structs with `#[serde(flatten)]` fields are deserialized as maps and are
+    /// not checked.
+    pub fn deny_unknown_fields(mut self, v: bool) -> Self {
+        self.deny_unknown_fields = v;
+        self
+    }
+
+    /// Limit how deep nested values can be while deserializing, default to `128`.
+    ///
+    /// Every `Some`, newtype, `Shared` and element of seqs, maps, structs and
+    /// variants adds a level. Values nested deeper than the limit return an error instead of
+    /// overflowing the stack.
+    pub fn max_depth(mut self, v: usize) -> Self {
+        self.max_depth = v;
+        self
+    }
+
+    /// Serialize non-finite `Value::F32`/`Value::F64` following given [`NanHandling`].
+    ///
+    /// Formats like JSON can't represent `NaN` and infinities. By default they are
+    /// passed to the serializer as is, for example `serde_json` writes `null` while
+    /// stricter formats may fail.
+    pub fn nan_handling(mut self, v: NanHandling) -> Self {
+        self.nan_handling = v;
+        self
+    }
+
+    /// Reject `Value::F64` that can't be narrowed into `f32` exactly.
+    ///
+    /// Formats like JSON read every float as `Value::F64`. By default deserializing
+    /// `f32` from it narrows like `as` does, so `0.1` silently becomes the nearest
+    /// `f32`. When enabled, narrowing that loses precision or overflows returns an
+    /// error instead. `NaN` is always accepted.
+    pub fn exact_f32(mut self, v: bool) -> Self {
+        self.exact_f32 = v;
+        self
+    }
+
+    /// Enter a nested value, returning the config for it.
+    pub(crate) fn descend(mut self) -> Result<Self, Error> {
+        if self.max_depth == 0 {
+            return Err(Error(anyhow!("recursion limit exceeded")));
+        }
+        self.max_depth -= 1;
+        Ok(self)
+    }
+}
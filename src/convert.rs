@@ -0,0 +1,131 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{from_value, from_value_with_config, into_value, Config, Error, Value};
+
+/// Convert `T` into a structurally compatible `U` through [`Value`](crate::Value).
+///
+/// This is `from_value(into_value(value)?)` in one call with default [`Config`].
+///
+/// # Examples
+///
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use serde_bridge::convert;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// #[derive(Serialize)]
+/// struct Point {
+///     x: u8,
+///     y: u8,
+/// }
+/// #[derive(Deserialize)]
+/// struct Position {
+///     y: i64,
+///     x: i64,
+/// }
+///
+/// let p: Position = convert(Point { x: 1, y: 2 })?;
+/// assert_eq!((p.x, p.y), (1, 2));
+/// # Ok(())
+/// # }
+/// ```
+pub fn convert<T: Serialize, U: DeserializeOwned>(value: T) -> Result<U, Error> {
+    convert_with_config(value, Config::default())
+}
+
+/// Convert `T` into a structurally compatible `U` through [`Value`](crate::Value)
+/// with given [`Config`].
+///
+/// The config applies while deserializing into `U`, for example:
+///
+/// - [`Config::lenient`] parses strings of `T` into numbers and bools of `U`.
+/// - [`Config::allow_short_seq`] fills missing trailing fields when `T` is a tuple.
+pub fn convert_with_config<T: Serialize, U: DeserializeOwned>(
+    value: T,
+    config: Config,
+) -> Result<U, Error> {
+    from_value_with_config(into_value(value)?, config)
+}
+
+/// Implement `TryFrom<Value>` for scalars as thin wrappers over [`from_value`].
+///
+/// Integers and floats accept any numeric variant that fits, for example
+/// `u8::try_from(Value::I64(1))` works while `u8::try_from(Value::I64(-1))` fails.
+macro_rules! impl_try_from_value {
+    ($($ty:ty),*) => {
+        $(
+            impl TryFrom<Value> for $ty {
+                type Error = Error;
+
+                fn try_from(v: Value) -> Result<Self, Self::Error> {
+                    from_value(v)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_value!(
+    bool, i8, i16, i32, i64, i128, u8, u16, u32, u64, u128, f32, f64, char, String
+);
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[test]
+    fn test_convert() -> Result<()> {
+        #[derive(Serialize)]
+        struct Record {
+            id: u8,
+            name: &'static str,
+            tags: Vec<&'static str>,
+            extra: bool,
+        }
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Row {
+            tags: Vec<String>,
+            name: String,
+            id: u64,
+        }
+
+        let row: Row = convert(Record {
+            id: 7,
+            name: "seven",
+            tags: vec!["odd"],
+            extra: true,
+        })?;
+        assert_eq!(
+            row,
+            Row {
+                tags: vec!["odd".to_string()],
+                name: "seven".to_string(),
+                id: 7,
+            }
+        );
+
+        let port: u16 = convert_with_config("8080", Config::new().lenient(true))?;
+        assert_eq!(port, 8080);
+        assert!(convert::<_, u16>("8080").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_from_value() -> Result<()> {
+        assert!(bool::try_from(Value::Bool(true))?);
+        assert_eq!(u8::try_from(Value::I64(7))?, 7);
+        assert_eq!(i64::try_from(Value::U8(7))?, 7);
+        assert_eq!(f64::try_from(Value::F32(1.5))?, 1.5);
+        assert_eq!(char::try_from(Value::Char('a'))?, 'a');
+        assert_eq!(String::try_from(Value::Str("a".to_string()))?, "a");
+
+        let v: Result<u8, _> = Value::I64(-1).try_into();
+        assert!(v.is_err());
+        assert!(bool::try_from(Value::Str("true".to_string())).is_err());
+        Ok(())
+    }
+}
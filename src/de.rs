@@ -1,4 +1,8 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
 use std::fmt::Formatter;
+use std::rc::Rc;
+use std::sync::Arc;
 use std::vec::IntoIter;
 
 use anyhow::anyhow;
@@ -6,7 +10,10 @@ use indexmap::IndexMap;
 use serde::de::{DeserializeOwned, DeserializeSeed, MapAccess, SeqAccess, Visitor};
 use serde::{de, Deserialize};
 
-use crate::{Error, Value};
+use crate::number::f64_to_f32_exact;
+use crate::path::escape_token;
+use crate::transform::key_to_string;
+use crate::{Config, Error, Value};
 
 /// Convert [`Value`] into `T: DeserializeOwned`.
 ///
@@ -22,7 +29,236 @@ use crate::{Error, Value};
 /// # }
 /// ```
 pub fn from_value<T: DeserializeOwned>(v: Value) -> Result<T, Error> {
-    T::deserialize(Deserializer(v))
+    from_value_with_config(v, Config::default())
+}
+
+/// Convert [`Value`] into `T: DeserializeOwned` with given [`Config`].
+///
+/// # Examples
+///
+/// ```
+/// use serde_bridge::{from_value_with_config, Config, Value};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let v: bool = from_value_with_config(Value::Bool(true), Config::default())?;
+/// # assert!(v);
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_value_with_config<T: DeserializeOwned>(v: Value, config: Config) -> Result<T, Error> {
+    T::deserialize(Deserializer::with_config(v, config))
+}
+
+/// Deserialize [`Value`] into an existing `target`, reusing its allocations when possible.
+///
+/// This drives serde's `Deserialize::deserialize_in_place`. Types like `Vec<T>`
+/// reuse their buffers, while types without an in-place implementation (including
+/// `#[derive(Deserialize)]` structs unless serde's `deserialize_in_place` feature is
+/// enabled) fall back to replacing `target` with a fresh value.
+///
+/// On error, `target` may be left partially updated.
+///
+/// # Examples
+///
+/// ```
+/// use serde_bridge::{from_value_in_place, Value};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut target = vec![0u8; 8];
+/// from_value_in_place(Value::Seq(vec![Value::U8(1)]), &mut target)?;
+/// assert_eq!(target, vec![1]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_value_in_place<T: DeserializeOwned>(v: Value, target: &mut T) -> Result<(), Error> {
+    T::deserialize_in_place(Deserializer::new(v), target)
+}
+
+/// Convert [`Value`] into `T: DeserializeOwned`, reporting values that `T` skipped.
+///
+/// Returns JSON pointers of map entries and struct fields that were not read by
+/// `T`, for example unknown keys of a struct without `deny_unknown_fields`. This is
+/// useful to warn users about ignored config keys.
+///
+/// Values buffered by serde itself, like unknown keys collected by
+/// `#[serde(flatten)]` or untagged enums, are not reported.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_bridge::{from_value_tracked, Value};
+/// # use anyhow::Result;
+/// # use indexmap::indexmap;
+/// # fn main() -> Result<()> {
+/// #[derive(Deserialize)]
+/// struct Config {
+///     port: u16,
+/// }
+///
+/// let v = Value::Map(indexmap! {
+///     Value::Str("port".to_string()) => Value::U16(80),
+///     Value::Str("prot".to_string()) => Value::U16(81),
+/// });
+/// let (config, unused): (Config, _) = from_value_tracked(v)?;
+/// assert_eq!(config.port, 80);
+/// assert_eq!(unused, vec!["/prot".to_string()]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_value_tracked<T: DeserializeOwned>(v: Value) -> Result<(T, Vec<String>), Error> {
+    let unused = Rc::new(RefCell::new(Vec::new()));
+    let track = Tracker {
+        unused: unused.clone(),
+        path: String::new(),
+    };
+
+    let mut de = Deserializer::new(v);
+    de.2 = Some(track);
+    let t = T::deserialize(de)?;
+    let unused = unused.take();
+    Ok((t, unused))
+}
+
+/// Convert [`Value`] into `T: DeserializeOwned`, collecting errors of all top-level fields.
+///
+/// [`from_value`] stops at the first error, this function keeps going so that all
+/// bad fields can be reported at once, for example in a form validation UI.
+///
+/// # Scope
+///
+/// Only errors of top-level map entries and struct fields are accumulated: a
+/// failed field is recorded and removed, then `T` is deserialized again until no
+/// more field fails. Errors are prefixed with the field name like ``field `port`:``.
+///
+/// - Errors nested deeper are reported once per top-level field.
+/// - `missing field` errors caused by removing failed fields are not reported.
+/// - Other errors, or any error of non-map values, stop collecting.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_bridge::{from_value_collect_errors, Value};
+/// # use indexmap::indexmap;
+/// #[derive(Deserialize, Debug)]
+/// struct Config {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// let v = Value::Map(indexmap! {
+///     Value::Str("host".to_string()) => Value::Bool(true),
+///     Value::Str("port".to_string()) => Value::Str("80".to_string()),
+/// });
+/// let errors = from_value_collect_errors::<Config>(v).unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// ```
+pub fn from_value_collect_errors<T: DeserializeOwned>(mut v: Value) -> Result<T, Vec<Error>> {
+    // Strip `Value::Shared` in a loop so that long chains don't overflow the stack.
+    while let Value::Shared(s) = v {
+        v = unwrap_shared(s);
+    }
+    let mut entries: IndexMap<Value, Value> = match v {
+        Value::Map(m) => m,
+        Value::Struct(_, fields) => struct_entries(fields).collect(),
+        v => return from_value(v).map_err(|e| vec![e]),
+    };
+
+    let mut errors = Vec::new();
+    let mut removed = Vec::new();
+    loop {
+        let failed = RefCell::new(None);
+        let de = FieldsDeserializer {
+            entries: entries.clone(),
+            failed: &failed,
+        };
+        match T::deserialize(de) {
+            Ok(t) if errors.is_empty() => return Ok(t),
+            Ok(_) => return Err(errors),
+            Err(e) => match failed.take() {
+                Some(k) => {
+                    errors.push(Error(anyhow!("field `{}`: {}", key_to_string(&k), e)));
+                    entries.shift_remove(&k);
+                    removed.push(format!("missing field `{}`", key_to_string(&k)));
+                }
+                None => {
+                    if !removed.contains(&e.to_string()) {
+                        errors.push(e)
+                    }
+                    return Err(errors);
+                }
+            },
+        }
+    }
+}
+
+/// Present entries as a map and record the key whose value failed to deserialize.
+struct FieldsDeserializer<'a> {
+    entries: IndexMap<Value, Value>,
+    failed: &'a RefCell<Option<Value>>,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for FieldsDeserializer<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, vis: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        vis.visit_map(FieldsAccessor {
+            entries: self.entries.into_iter(),
+            current: None,
+            failed: self.failed,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct FieldsAccessor<'a> {
+    entries: indexmap::map::IntoIter<Value, Value>,
+    current: Option<(Value, Value)>,
+    failed: &'a RefCell<Option<Value>>,
+}
+
+impl<'de, 'a> MapAccess<'de> for FieldsAccessor<'a> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.entries.next() {
+            None => Ok(None),
+            Some((k, v)) => {
+                let key = seed.deserialize(Deserializer::new(k.clone()))?;
+                self.current = Some((k, v));
+                Ok(Some(key))
+            }
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (k, v) = self
+            .current
+            .take()
+            .ok_or_else(|| Error(anyhow!("value is missing")))?;
+        seed.deserialize(Deserializer::new(v)).inspect_err(|_| {
+            *self.failed.borrow_mut() = Some(k);
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
 }
 
 /// Convert [`Value`] into `T: DeserializeOwned`.
@@ -97,6 +333,13 @@ impl<'de> Visitor<'de> for ValueVisitor {
         Ok(Value::I64(v))
     }
 
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::I128(v))
+    }
+
     fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E>
     where
         E: de::Error,
@@ -125,6 +368,13 @@ impl<'de> Visitor<'de> for ValueVisitor {
         Ok(Value::U64(v))
     }
 
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Ok(Value::U128(v))
+    }
+
     fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
     where
         E: de::Error,
@@ -251,7 +501,130 @@ impl<'de> Deserialize<'de> for Value {
     }
 }
 
-struct Deserializer(Value);
+impl Value {
+    /// Deserialize elements of `Value::Seq` into `T: DeserializeOwned` lazily.
+    ///
+    /// Elements are only deserialized while the iterator is consumed, so callers can
+    /// stop early without paying for the rest. Errors carry the index of the failed
+    /// element. A non-seq value yields a single error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let v = Value::Seq(vec![Value::I32(1), Value::I32(2), Value::I32(3)]);
+    /// let first: Vec<i32> = v
+    ///     .deserialize_seq_iter::<i32>()
+    ///     .take(2)
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(first, vec![1, 2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deserialize_seq_iter<T: DeserializeOwned>(
+        self,
+    ) -> impl Iterator<Item = Result<T, Error>> {
+        let (elements, err) = match self {
+            Value::Seq(v) | Value::Tuple(v) => (v, None),
+            v => (
+                Vec::new(),
+                Some(Error(anyhow!("invalid type: {:?}, expect seq", v))),
+            ),
+        };
+
+        err.map(Err).into_iter().chain(
+            elements.into_iter().enumerate().map(|(idx, v)| {
+                from_value(v).map_err(|e| Error(anyhow!("element {}: {}", idx, e)))
+            }),
+        )
+    }
+}
+
+/// Deserializer is a [`serde::Deserializer`] that converts [`Value`] into `T: Deserialize`.
+///
+/// It can be passed to any API that accepts `impl serde::Deserializer`, for example
+/// wrappers like `serde_path_to_error::Deserializer`.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+/// use serde_bridge::{Deserializer, Value};
+/// # use anyhow::Result;
+///
+/// fn parse<'de, T: Deserialize<'de>>(d: impl serde::Deserializer<'de>) -> Result<T, String> {
+///     T::deserialize(d).map_err(|e| e.to_string())
+/// }
+///
+/// # fn main() -> Result<()>{
+/// let v: bool = parse(Deserializer::new(Value::Bool(true))).unwrap();
+/// assert!(v);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Wrapped by `serde_path_to_error` to find where deserializing failed:
+///
+/// ```
+/// use indexmap::indexmap;
+/// use serde::Deserialize;
+/// use serde_bridge::{Deserializer, Value};
+///
+/// #[derive(Deserialize, Debug)]
+/// struct Config {
+///     ports: Vec<u16>,
+/// }
+///
+/// let v = Value::Map(indexmap! {
+///     Value::Str("ports".to_string()) => Value::Seq(vec![Value::U16(80), Value::I32(-1)]),
+/// });
+/// let err = serde_path_to_error::deserialize::<_, Config>(Deserializer::new(v)).unwrap_err();
+/// assert_eq!(err.path().to_string(), "ports[1]");
+/// ```
+pub struct Deserializer(Value, Config, Option<Tracker>);
+
+impl Deserializer {
+    /// Create a new deserializer for [`Value`] with default [`Config`].
+    pub fn new(v: Value) -> Self {
+        Self::with_config(v, Config::default())
+    }
+
+    /// Create a new deserializer for [`Value`] with given [`Config`].
+    pub fn with_config(v: Value, config: Config) -> Self {
+        Self(v, config, None)
+    }
+
+    /// Present enum variant as a single entry map `{ variant: payload }`.
+    ///
+    /// This is the externally tagged representation used by self-describing
+    /// formats like JSON.
+    fn visit_variant_as_map<'de, V>(self, vis: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let (variant, payload) = match self.0 {
+            Value::NewtypeVariant { variant, value, .. } => (variant, *value),
+            Value::TupleVariant {
+                variant, fields, ..
+            } => (variant, Value::Tuple(fields)),
+            Value::StructVariant {
+                name,
+                variant,
+                fields,
+                ..
+            } => (variant, Value::Struct(name, fields)),
+            v => return Err(Error(anyhow!("invalid type: {:?}, expect variant", v))),
+        };
+
+        vis.visit_map(MapAccessor::new(
+            std::iter::once((variant, payload)),
+            self.1,
+            self.2,
+        ))
+    }
+}
 
 impl<'de> serde::Deserializer<'de> for Deserializer {
     type Error = Error;
@@ -283,7 +656,30 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
             Value::Map(_) => self.deserialize_map(vis),
             Value::Seq(_) => self.deserialize_seq(vis),
             Value::Struct(_, _) => self.deserialize_map(vis),
-            v => unimplemented!("deserialize_any for {:?}", v),
+            Value::UnitStruct(_) => vis.visit_unit(),
+            Value::UnitVariant { variant, .. } => vis.visit_borrowed_str(variant),
+            // Newtype structs are transparent like most self-describing formats,
+            // visitors like `serde_json::Value` don't implement `visit_newtype_struct`.
+            Value::NewtypeStruct(..) => match self.0 {
+                Value::NewtypeStruct(_, v) => {
+                    Deserializer(*v, self.1.descend()?, self.2).deserialize_any(vis)
+                }
+                _ => unreachable!("must be newtype struct"),
+            },
+            Value::Tuple(_) => self.deserialize_seq(vis),
+            Value::TupleStruct(vn, vf) => {
+                let (name, len) = (*vn, vf.len());
+                self.deserialize_tuple_struct(name, len, vis)
+            }
+            Value::NewtypeVariant { .. }
+            | Value::TupleVariant { .. }
+            | Value::StructVariant { .. } => self.visit_variant_as_map(vis),
+            Value::Shared(_) => match self.0 {
+                Value::Shared(v) => {
+                    Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_any(vis)
+                }
+                _ => unreachable!("must be shared"),
+            },
         }
     }
 
@@ -293,6 +689,10 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     {
         match self.0 {
             Value::Bool(v) => vis.visit_bool(v),
+            Value::Str(v) if self.1.lenient => vis.visit_bool(parse_lenient(&v, "bool")?),
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_bool(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}", v))),
         }
     }
@@ -303,15 +703,22 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     {
         match self.0 {
             Value::I8(v) => vis.visit_i8(v),
-            Value::I16(v) => vis.visit_i8(i8::try_from(v)?),
-            Value::I32(v) => vis.visit_i8(i8::try_from(v)?),
-            Value::I64(v) => vis.visit_i8(i8::try_from(v)?),
-            Value::I128(v) => vis.visit_i8(i8::try_from(v)?),
-            Value::U8(v) => vis.visit_i8(i8::try_from(v)?),
-            Value::U16(v) => vis.visit_i8(i8::try_from(v)?),
-            Value::U32(v) => vis.visit_i8(i8::try_from(v)?),
-            Value::U64(v) => vis.visit_i8(i8::try_from(v)?),
-            Value::U128(v) => vis.visit_i8(i8::try_from(v)?),
+            Value::I16(v) => vis.visit_i8(narrow(v, "i8")?),
+            Value::I32(v) => vis.visit_i8(narrow(v, "i8")?),
+            Value::I64(v) => vis.visit_i8(narrow(v, "i8")?),
+            Value::I128(v) => vis.visit_i8(narrow(v, "i8")?),
+            Value::U8(v) => vis.visit_i8(narrow(v, "i8")?),
+            Value::U16(v) => vis.visit_i8(narrow(v, "i8")?),
+            Value::U32(v) => vis.visit_i8(narrow(v, "i8")?),
+            Value::U64(v) => vis.visit_i8(narrow(v, "i8")?),
+            Value::U128(v) => vis.visit_i8(narrow(v, "i8")?),
+            Value::Str(v) if self.1.lenient => vis.visit_i8(parse_lenient(&v, "i8")?),
+            v @ (Value::F32(_) | Value::F64(_)) if self.1.lenient => {
+                vis.visit_i8(float_to_int(&v, "i8")?)
+            }
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_i8(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect i8", v))),
         }
     }
@@ -323,14 +730,21 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
         match self.0 {
             Value::I8(v) => vis.visit_i16(i16::from(v)),
             Value::I16(v) => vis.visit_i16(v),
-            Value::I32(v) => vis.visit_i16(i16::try_from(v)?),
-            Value::I64(v) => vis.visit_i16(i16::try_from(v)?),
-            Value::I128(v) => vis.visit_i16(i16::try_from(v)?),
+            Value::I32(v) => vis.visit_i16(narrow(v, "i16")?),
+            Value::I64(v) => vis.visit_i16(narrow(v, "i16")?),
+            Value::I128(v) => vis.visit_i16(narrow(v, "i16")?),
             Value::U8(v) => vis.visit_i16(i16::from(v)),
-            Value::U16(v) => vis.visit_i16(i16::try_from(v)?),
-            Value::U32(v) => vis.visit_i16(i16::try_from(v)?),
-            Value::U64(v) => vis.visit_i16(i16::try_from(v)?),
-            Value::U128(v) => vis.visit_i16(i16::try_from(v)?),
+            Value::U16(v) => vis.visit_i16(narrow(v, "i16")?),
+            Value::U32(v) => vis.visit_i16(narrow(v, "i16")?),
+            Value::U64(v) => vis.visit_i16(narrow(v, "i16")?),
+            Value::U128(v) => vis.visit_i16(narrow(v, "i16")?),
+            Value::Str(v) if self.1.lenient => vis.visit_i16(parse_lenient(&v, "i16")?),
+            v @ (Value::F32(_) | Value::F64(_)) if self.1.lenient => {
+                vis.visit_i16(float_to_int(&v, "i16")?)
+            }
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_i16(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect i16", v))),
         }
     }
@@ -343,13 +757,20 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
             Value::I8(v) => vis.visit_i32(i32::from(v)),
             Value::I16(v) => vis.visit_i32(i32::from(v)),
             Value::I32(v) => vis.visit_i32(v),
-            Value::I64(v) => vis.visit_i32(i32::try_from(v)?),
-            Value::I128(v) => vis.visit_i32(i32::try_from(v)?),
+            Value::I64(v) => vis.visit_i32(narrow(v, "i32")?),
+            Value::I128(v) => vis.visit_i32(narrow(v, "i32")?),
             Value::U8(v) => vis.visit_i32(i32::from(v)),
             Value::U16(v) => vis.visit_i32(i32::from(v)),
-            Value::U32(v) => vis.visit_i32(i32::try_from(v)?),
-            Value::U64(v) => vis.visit_i32(i32::try_from(v)?),
-            Value::U128(v) => vis.visit_i32(i32::try_from(v)?),
+            Value::U32(v) => vis.visit_i32(narrow(v, "i32")?),
+            Value::U64(v) => vis.visit_i32(narrow(v, "i32")?),
+            Value::U128(v) => vis.visit_i32(narrow(v, "i32")?),
+            Value::Str(v) if self.1.lenient => vis.visit_i32(parse_lenient(&v, "i32")?),
+            v @ (Value::F32(_) | Value::F64(_)) if self.1.lenient => {
+                vis.visit_i32(float_to_int(&v, "i32")?)
+            }
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_i32(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}", v))),
         }
     }
@@ -363,31 +784,71 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
             Value::I16(v) => vis.visit_i64(i64::from(v)),
             Value::I32(v) => vis.visit_i64(i64::from(v)),
             Value::I64(v) => vis.visit_i64(v),
-            Value::I128(v) => vis.visit_i64(i64::try_from(v)?),
+            Value::I128(v) => vis.visit_i64(narrow(v, "i64")?),
             Value::U8(v) => vis.visit_i64(i64::from(v)),
             Value::U16(v) => vis.visit_i32(i32::from(v)),
             Value::U32(v) => vis.visit_i64(i64::from(v)),
-            Value::U64(v) => vis.visit_i64(i64::try_from(v)?),
-            Value::U128(v) => vis.visit_i64(i64::try_from(v)?),
+            Value::U64(v) => vis.visit_i64(narrow(v, "i64")?),
+            Value::U128(v) => vis.visit_i64(narrow(v, "i64")?),
+            Value::Str(v) if self.1.lenient => vis.visit_i64(parse_lenient(&v, "i64")?),
+            v @ (Value::F32(_) | Value::F64(_)) if self.1.lenient => {
+                vis.visit_i64(float_to_int(&v, "i64")?)
+            }
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_i64(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect i64", v))),
         }
     }
 
+    fn deserialize_i128<V>(self, vis: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::I8(v) => vis.visit_i128(i128::from(v)),
+            Value::I16(v) => vis.visit_i128(i128::from(v)),
+            Value::I32(v) => vis.visit_i128(i128::from(v)),
+            Value::I64(v) => vis.visit_i128(i128::from(v)),
+            Value::I128(v) => vis.visit_i128(v),
+            Value::U8(v) => vis.visit_i128(i128::from(v)),
+            Value::U16(v) => vis.visit_i128(i128::from(v)),
+            Value::U32(v) => vis.visit_i128(i128::from(v)),
+            Value::U64(v) => vis.visit_i128(i128::from(v)),
+            Value::U128(v) => vis.visit_i128(narrow(v, "i128")?),
+            Value::Str(v) if self.1.lenient => vis.visit_i128(parse_lenient(&v, "i128")?),
+            v @ (Value::F32(_) | Value::F64(_)) if self.1.lenient => {
+                vis.visit_i128(float_to_int(&v, "i128")?)
+            }
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_i128(vis)
+            }
+            v => Err(Error(anyhow!("invalid type: {:?}, expect i128", v))),
+        }
+    }
+
     fn deserialize_u8<V>(self, vis: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
         match self.0 {
-            Value::I8(v) => vis.visit_u8(u8::try_from(v)?),
-            Value::I16(v) => vis.visit_u8(u8::try_from(v)?),
-            Value::I32(v) => vis.visit_u8(u8::try_from(v)?),
-            Value::I64(v) => vis.visit_u8(u8::try_from(v)?),
-            Value::I128(v) => vis.visit_u8(u8::try_from(v)?),
+            Value::I8(v) => vis.visit_u8(narrow(v, "u8")?),
+            Value::I16(v) => vis.visit_u8(narrow(v, "u8")?),
+            Value::I32(v) => vis.visit_u8(narrow(v, "u8")?),
+            Value::I64(v) => vis.visit_u8(narrow(v, "u8")?),
+            Value::I128(v) => vis.visit_u8(narrow(v, "u8")?),
             Value::U8(v) => vis.visit_u8(v),
-            Value::U16(v) => vis.visit_u8(u8::try_from(v)?),
-            Value::U32(v) => vis.visit_u8(u8::try_from(v)?),
-            Value::U64(v) => vis.visit_u8(u8::try_from(v)?),
-            Value::U128(v) => vis.visit_u8(u8::try_from(v)?),
+            Value::U16(v) => vis.visit_u8(narrow(v, "u8")?),
+            Value::U32(v) => vis.visit_u8(narrow(v, "u8")?),
+            Value::U64(v) => vis.visit_u8(narrow(v, "u8")?),
+            Value::U128(v) => vis.visit_u8(narrow(v, "u8")?),
+            Value::Str(v) if self.1.lenient => vis.visit_u8(parse_lenient(&v, "u8")?),
+            v @ (Value::F32(_) | Value::F64(_)) if self.1.lenient => {
+                vis.visit_u8(float_to_int(&v, "u8")?)
+            }
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_u8(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect u8", v))),
         }
     }
@@ -397,16 +858,23 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
         V: Visitor<'de>,
     {
         match self.0 {
-            Value::I8(v) => vis.visit_u16(u16::try_from(v)?),
-            Value::I16(v) => vis.visit_u16(u16::try_from(v)?),
-            Value::I32(v) => vis.visit_u16(u16::try_from(v)?),
-            Value::I64(v) => vis.visit_u16(u16::try_from(v)?),
-            Value::I128(v) => vis.visit_u16(u16::try_from(v)?),
+            Value::I8(v) => vis.visit_u16(narrow(v, "u16")?),
+            Value::I16(v) => vis.visit_u16(narrow(v, "u16")?),
+            Value::I32(v) => vis.visit_u16(narrow(v, "u16")?),
+            Value::I64(v) => vis.visit_u16(narrow(v, "u16")?),
+            Value::I128(v) => vis.visit_u16(narrow(v, "u16")?),
             Value::U8(v) => vis.visit_u16(u16::from(v)),
             Value::U16(v) => vis.visit_u16(v),
-            Value::U32(v) => vis.visit_u16(u16::try_from(v)?),
-            Value::U64(v) => vis.visit_u16(u16::try_from(v)?),
-            Value::U128(v) => vis.visit_u16(u16::try_from(v)?),
+            Value::U32(v) => vis.visit_u16(narrow(v, "u16")?),
+            Value::U64(v) => vis.visit_u16(narrow(v, "u16")?),
+            Value::U128(v) => vis.visit_u16(narrow(v, "u16")?),
+            Value::Str(v) if self.1.lenient => vis.visit_u16(parse_lenient(&v, "u16")?),
+            v @ (Value::F32(_) | Value::F64(_)) if self.1.lenient => {
+                vis.visit_u16(float_to_int(&v, "u16")?)
+            }
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_u16(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect u16", v))),
         }
     }
@@ -416,16 +884,23 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
         V: Visitor<'de>,
     {
         match self.0 {
-            Value::I8(v) => vis.visit_u32(u32::try_from(v)?),
-            Value::I16(v) => vis.visit_u32(u32::try_from(v)?),
-            Value::I32(v) => vis.visit_u32(u32::try_from(v)?),
-            Value::I64(v) => vis.visit_u32(u32::try_from(v)?),
-            Value::I128(v) => vis.visit_u32(u32::try_from(v)?),
+            Value::I8(v) => vis.visit_u32(narrow(v, "u32")?),
+            Value::I16(v) => vis.visit_u32(narrow(v, "u32")?),
+            Value::I32(v) => vis.visit_u32(narrow(v, "u32")?),
+            Value::I64(v) => vis.visit_u32(narrow(v, "u32")?),
+            Value::I128(v) => vis.visit_u32(narrow(v, "u32")?),
             Value::U8(v) => vis.visit_u32(u32::from(v)),
             Value::U16(v) => vis.visit_u32(u32::from(v)),
             Value::U32(v) => vis.visit_u32(v),
-            Value::U64(v) => vis.visit_u32(u32::try_from(v)?),
-            Value::U128(v) => vis.visit_u32(u32::try_from(v)?),
+            Value::U64(v) => vis.visit_u32(narrow(v, "u32")?),
+            Value::U128(v) => vis.visit_u32(narrow(v, "u32")?),
+            Value::Str(v) if self.1.lenient => vis.visit_u32(parse_lenient(&v, "u32")?),
+            v @ (Value::F32(_) | Value::F64(_)) if self.1.lenient => {
+                vis.visit_u32(float_to_int(&v, "u32")?)
+            }
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_u32(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect u32", v))),
         }
     }
@@ -435,27 +910,71 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
         V: Visitor<'de>,
     {
         match self.0 {
-            Value::I8(v) => vis.visit_u64(u64::try_from(v)?),
-            Value::I16(v) => vis.visit_u64(u64::try_from(v)?),
-            Value::I32(v) => vis.visit_u64(u64::try_from(v)?),
-            Value::I64(v) => vis.visit_u64(u64::try_from(v)?),
-            Value::I128(v) => vis.visit_u64(u64::try_from(v)?),
+            Value::I8(v) => vis.visit_u64(narrow(v, "u64")?),
+            Value::I16(v) => vis.visit_u64(narrow(v, "u64")?),
+            Value::I32(v) => vis.visit_u64(narrow(v, "u64")?),
+            Value::I64(v) => vis.visit_u64(narrow(v, "u64")?),
+            Value::I128(v) => vis.visit_u64(narrow(v, "u64")?),
             Value::U8(v) => vis.visit_u64(u64::from(v)),
             Value::U16(v) => vis.visit_u64(u64::from(v)),
             Value::U32(v) => vis.visit_u64(u64::from(v)),
             Value::U64(v) => vis.visit_u64(v),
-            Value::U128(v) => vis.visit_u64(u64::try_from(v)?),
+            Value::U128(v) => vis.visit_u64(narrow(v, "u64")?),
+            Value::Str(v) if self.1.lenient => vis.visit_u64(parse_lenient(&v, "u64")?),
+            v @ (Value::F32(_) | Value::F64(_)) if self.1.lenient => {
+                vis.visit_u64(float_to_int(&v, "u64")?)
+            }
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_u64(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect u64", v))),
         }
     }
 
+    fn deserialize_u128<V>(self, vis: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.0 {
+            Value::I8(v) => vis.visit_u128(narrow(v, "u128")?),
+            Value::I16(v) => vis.visit_u128(narrow(v, "u128")?),
+            Value::I32(v) => vis.visit_u128(narrow(v, "u128")?),
+            Value::I64(v) => vis.visit_u128(narrow(v, "u128")?),
+            Value::I128(v) => vis.visit_u128(narrow(v, "u128")?),
+            Value::U8(v) => vis.visit_u128(u128::from(v)),
+            Value::U16(v) => vis.visit_u128(u128::from(v)),
+            Value::U32(v) => vis.visit_u128(u128::from(v)),
+            Value::U64(v) => vis.visit_u128(u128::from(v)),
+            Value::U128(v) => vis.visit_u128(v),
+            Value::Str(v) if self.1.lenient => vis.visit_u128(parse_lenient(&v, "u128")?),
+            v @ (Value::F32(_) | Value::F64(_)) if self.1.lenient => {
+                vis.visit_u128(float_to_int(&v, "u128")?)
+            }
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_u128(vis)
+            }
+            v => Err(Error(anyhow!("invalid type: {:?}, expect u128", v))),
+        }
+    }
+
     fn deserialize_f32<V>(self, vis: V) -> Result<V::Value, Self::Error>
     where
         V: Visitor<'de>,
     {
         match self.0 {
             Value::F32(v) => vis.visit_f32(v),
+            Value::F64(v) if self.1.exact_f32 => match f64_to_f32_exact(v) {
+                Some(f) => vis.visit_f32(f),
+                None => Err(Error(anyhow!(
+                    "f64 {} can't be represented as f32 exactly",
+                    v
+                ))),
+            },
             Value::F64(v) => vis.visit_f32(v as f32),
+            Value::Str(v) if self.1.lenient => vis.visit_f32(parse_lenient(&v, "f32")?),
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_f32(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect f32", v))),
         }
     }
@@ -467,6 +986,10 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
         match self.0 {
             Value::F32(v) => vis.visit_f64(f64::from(v)),
             Value::F64(v) => vis.visit_f64(v),
+            Value::Str(v) if self.1.lenient => vis.visit_f64(parse_lenient(&v, "f64")?),
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_f64(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect f64", v))),
         }
     }
@@ -477,6 +1000,16 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     {
         match self.0 {
             Value::Char(v) => vis.visit_char(v),
+            // Binary formats may store chars as code points.
+            v if self.1.lenient && v.kind().is_integer() => vis.visit_char(
+                v.to_u128()
+                    .and_then(|c| u32::try_from(c).ok())
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| Error(anyhow!("invalid char: {:?}", v)))?,
+            ),
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_char(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect char", v))),
         }
     }
@@ -487,6 +1020,9 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     {
         match self.0 {
             Value::Str(v) => vis.visit_string(v),
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_str(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect str", v))),
         }
     }
@@ -497,6 +1033,9 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     {
         match self.0 {
             Value::Str(v) => vis.visit_string(v),
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_string(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect string", v))),
         }
     }
@@ -507,6 +1046,11 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     {
         match self.0 {
             Value::Bytes(v) => vis.visit_byte_buf(v),
+            // Formats without bytes like JSON emit them as seqs.
+            Value::Seq(v) => vis.visit_byte_buf(seq_to_bytes(v)?),
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_bytes(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect bytes", v))),
         }
     }
@@ -517,6 +1061,10 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     {
         match self.0 {
             Value::Bytes(v) => vis.visit_byte_buf(v),
+            Value::Seq(v) => vis.visit_byte_buf(seq_to_bytes(v)?),
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_byte_buf(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect bytes_buf", v))),
         }
     }
@@ -527,7 +1075,16 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     {
         match self.0 {
             Value::None => vis.visit_none(),
-            Value::Some(v) => vis.visit_some(Deserializer(*v)),
+            Value::Some(v) => vis.visit_some(Deserializer(*v, self.1.descend()?, self.2)),
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_option(vis)
+            }
+            // Self-describing formats like JSON have no options: `null` becomes
+            // `Value::Unit` and other values are present.
+            Value::Unit if self.1.option_from_plain => vis.visit_none(),
+            v if self.1.option_from_plain => {
+                vis.visit_some(Deserializer(v, self.1.descend()?, self.2))
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect option", v))),
         }
     }
@@ -537,7 +1094,13 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
         V: Visitor<'de>,
     {
         match self.0 {
-            Value::Unit => vis.visit_unit(),
+            // `null` becomes `Value::None` in formats that call `visit_none` for it.
+            Value::Unit | Value::None => vis.visit_unit(),
+            // `()` is the zero-length tuple.
+            Value::Tuple(v) if v.is_empty() => vis.visit_unit(),
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_unit(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect unit", v))),
         }
     }
@@ -548,6 +1111,9 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     {
         match self.0 {
             Value::UnitStruct(vn) if vn == name => vis.visit_unit(),
+            Value::Unit | Value::None => vis.visit_unit(),
+            Value::Shared(v) => Deserializer(unwrap_shared(v), self.1.descend()?, self.2)
+                .deserialize_unit_struct(name, vis),
             v => Err(Error(anyhow!("invalid type: {:?}, expect unit struct", v))),
         }
     }
@@ -561,9 +1127,14 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
         V: Visitor<'de>,
     {
         match self.0 {
-            Value::NewtypeStruct(vn, vv) if vn == name => {
-                vis.visit_newtype_struct(Deserializer(*vv))
+            // Names are not checked, same as `deserialize_struct`. Newtype
+            // structs built by `Value`'s own `Deserialize` impl don't know
+            // their name at all.
+            Value::NewtypeStruct(_, vv) => {
+                vis.visit_newtype_struct(Deserializer(*vv, self.1.descend()?, self.2))
             }
+            Value::Shared(v) => Deserializer(unwrap_shared(v), self.1.descend()?, self.2)
+                .deserialize_newtype_struct(name, vis),
             v => Err(Error(anyhow!(
                 "invalid type: {:?}, expect newtype struct",
                 v
@@ -576,8 +1147,12 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
         V: Visitor<'de>,
     {
         match self.0 {
-            Value::Tuple(v) => vis.visit_seq(SeqAccessor::new(v)),
-            Value::Seq(v) => vis.visit_seq(SeqAccessor::new(v)),
+            Value::Tuple(v) => vis.visit_seq(SeqAccessor::new(v, self.1, self.2)),
+            Value::Seq(v) => vis.visit_seq(SeqAccessor::new(v, self.1, self.2)),
+            Value::Bytes(v) => vis.visit_seq(SeqAccessor::new(bytes_to_seq(v), self.1, self.2)),
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_seq(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect seq", v))),
         }
     }
@@ -587,8 +1162,21 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
         V: Visitor<'de>,
     {
         match self.0 {
-            Value::Tuple(v) if len == v.len() => vis.visit_seq(SeqAccessor::new(v)),
-            Value::Seq(v) if len == v.len() => vis.visit_seq(SeqAccessor::new(v)),
+            Value::Tuple(v) if len == v.len() => vis.visit_seq(SeqAccessor::new(v, self.1, self.2)),
+            Value::Seq(v) if len == v.len() => vis.visit_seq(SeqAccessor::new(v, self.1, self.2)),
+            // Allow deserializing bytes into `[u8; N]`.
+            Value::Bytes(v) if len == v.len() => {
+                vis.visit_seq(SeqAccessor::new(bytes_to_seq(v), self.1, self.2))
+            }
+            Value::Unit if len == 0 && self.1.unit_as_empty_tuple => {
+                vis.visit_seq(SeqAccessor::new(Vec::new(), self.1, self.2))
+            }
+            Value::Map(v) if len == v.len() && self.1.lenient => {
+                let v = index_map_to_seq(v)?;
+                vis.visit_seq(SeqAccessor::new(v, self.1, self.2))
+            }
+            Value::Shared(v) => Deserializer(unwrap_shared(v), self.1.descend()?, self.2)
+                .deserialize_tuple(len, vis),
             v => Err(Error(anyhow!("invalid type: {:?}, expect tuple", v))),
         }
     }
@@ -604,8 +1192,14 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     {
         match self.0 {
             Value::TupleStruct(vn, vf) if name == vn && len == vf.len() => {
-                vis.visit_seq(SeqAccessor::new(vf))
+                vis.visit_seq(SeqAccessor::new(vf, self.1, self.2))
+            }
+            // Self-describing formats like JSON don't carry the name.
+            Value::Seq(v) | Value::Tuple(v) if len == v.len() => {
+                vis.visit_seq(SeqAccessor::new(v, self.1, self.2))
             }
+            Value::Shared(v) => Deserializer(unwrap_shared(v), self.1.descend()?, self.2)
+                .deserialize_tuple_struct(name, len, vis),
             v => Err(Error(anyhow!("invalid type: {:?}, expect tuple struct", v))),
         }
     }
@@ -615,7 +1209,15 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
         V: Visitor<'de>,
     {
         match self.0 {
-            Value::Map(v) => vis.visit_map(MapAccessor::new(v)),
+            Value::Map(v) => vis.visit_map(MapAccessor::new(v, self.1, self.2)),
+            // Struct fields are presented as string keys, which also makes
+            // `#[serde(flatten)]` structs work since they use `deserialize_map`.
+            Value::Struct(_, fields) | Value::StructVariant { fields, .. } => {
+                vis.visit_map(MapAccessor::new(fields, self.1, self.2))
+            }
+            Value::Shared(v) => {
+                Deserializer(unwrap_shared(v), self.1.descend()?, self.2).deserialize_map(vis)
+            }
             v => Err(Error(anyhow!("invalid type: {:?}, expect map", v))),
         }
     }
@@ -630,20 +1232,40 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
         V: Visitor<'de>,
     {
         match self.0 {
-            Value::Struct(vn, mut vf) if vn == name => {
-                let mut vs = Vec::with_capacity(fields.len());
-                for key in fields {
-                    // Use `remove` instead of `get` & `clone` here.
-                    // - As serde will make sure to not access the same field twice.
-                    // - The order of key is not needed to preserve during deserialize.
-                    match vf.remove(key) {
-                        Some(v) => vs.push(v),
-                        None => return Err(Error(anyhow!("field not exist"))),
-                    }
+            // Always present struct as a map so that visitors which need to
+            // see all keys (like `#[serde(flatten)]`) work as expected.
+            //
+            // Struct names are not checked: fields are matched by name just like
+            // maps, which allows converting between compatible structs.
+            Value::Struct(_, vf) => {
+                if self.1.deny_unknown_fields {
+                    check_unknown_fields(vf.keys().copied(), fields)?;
+                }
+                vis.visit_map(MapAccessor::new(vf, self.1, self.2))
+            }
+            Value::Map(m) => {
+                if self.1.deny_unknown_fields {
+                    check_unknown_fields(m.keys().map(key_to_string), fields)?;
+                }
+                vis.visit_map(MapAccessor::new(m, self.1, self.2))
+            }
+            // Struct could be deserialized from seq by position.
+            Value::Seq(mut v) | Value::Tuple(mut v) => {
+                if v.len() < fields.len() && self.1.allow_short_seq {
+                    v.resize(fields.len(), Value::None);
+                }
+                if v.len() != fields.len() {
+                    return Err(Error(anyhow!(
+                        "invalid length: {}, expect struct {} with {} fields",
+                        v.len(),
+                        name,
+                        fields.len()
+                    )));
                 }
-                vis.visit_seq(SeqAccessor::new(vs))
+                vis.visit_seq(SeqAccessor::new(v, self.1, self.2))
             }
-            Value::Map(fields) => vis.visit_map(MapAccessor::new(fields)),
+            Value::Shared(v) => Deserializer(unwrap_shared(v), self.1.descend()?, self.2)
+                .deserialize_struct(name, fields, vis),
             v => Err(Error(anyhow!("invalid type: {:?}, expect struct", v))),
         }
     }
@@ -657,7 +1279,11 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     where
         V: Visitor<'de>,
     {
-        vis.visit_enum(EnumAccessor::new(name, variants, self.0))
+        match self.0 {
+            Value::Shared(v) => Deserializer(unwrap_shared(v), self.1.descend()?, self.2)
+                .deserialize_enum(name, variants, vis),
+            v => vis.visit_enum(EnumAccessor::new(name, variants, v, self.1, self.2)),
+        }
     }
 
     fn deserialize_identifier<V>(self, vis: V) -> Result<V::Value, Self::Error>
@@ -671,18 +1297,49 @@ impl<'de> serde::Deserializer<'de> for Deserializer {
     where
         V: Visitor<'de>,
     {
-        self.deserialize_any(vis)
+        match self.2 {
+            // The target type skipped this value, record it as unused.
+            Some(track) => {
+                track.unused.borrow_mut().push(track.path);
+                vis.visit_unit()
+            }
+            // The value is dropped without walking through it.
+            None => vis.visit_unit(),
+        }
+    }
+}
+
+/// Records values skipped by the target type, see [`from_value_tracked`].
+#[derive(Clone)]
+struct Tracker {
+    unused: Rc<RefCell<Vec<String>>>,
+    /// JSON pointer of the value being deserialized.
+    path: String,
+}
+
+impl Tracker {
+    fn child(&self, token: &str) -> Self {
+        Self {
+            unused: self.unused.clone(),
+            path: format!("{}/{}", self.path, escape_token(token)),
+        }
     }
 }
 
 struct SeqAccessor {
     elements: IntoIter<Value>,
+    config: Config,
+    track: Option<Tracker>,
+    idx: usize,
 }
 
 impl SeqAccessor {
-    fn new(elements: Vec<Value>) -> Self {
+    fn new(elements: Vec<Value>, config: Config, track: Option<Tracker>) -> Self {
         Self {
             elements: elements.into_iter(),
+            config,
+            track,
+            idx: 0,
         }
     }
 }
@@ -696,54 +1353,286 @@ impl<'de> de::SeqAccess<'de> for SeqAccessor {
     {
         match self.elements.next() {
             None => Ok(None),
-            Some(v) => Ok(Some(seed.deserialize(Deserializer(v))?)),
+            Some(v) => {
+                let track = self.track.as_ref().map(|t| t.child(&self.idx.to_string()));
+                self.idx += 1;
+                Ok(Some(seed.deserialize(Deserializer(
+                    v,
+                    self.config.descend()?,
+                    track,
+                ))?))
+            }
         }
     }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
 }
 
-struct MapAccessor {
-    cache_value: Option<Value>,
-    entries: indexmap::map::IntoIter<Value, Value>,
+/// Parse a scalar from string in lenient mode.
+fn parse_lenient<F: std::str::FromStr>(v: &str, expect: &str) -> Result<F, Error> {
+    v.parse()
+        .map_err(|_| Error(anyhow!("invalid {}: {:?}", expect, v)))
 }
 
-impl MapAccessor {
-    fn new(entries: IndexMap<Value, Value>) -> Self {
-        Self {
-            cache_value: None,
-            entries: entries.into_iter(),
+/// Convert integer `v` into a narrower integer `T`.
+fn narrow<T: TryFrom<S>, S: std::fmt::Display + Copy>(v: S, expect: &str) -> Result<T, Error> {
+    T::try_from(v).map_err(|_| Error(anyhow!("value {} out of range for {}", v, expect)))
+}
+
+/// Convert an integral float into integer `T`.
+fn float_to_int<T: TryFrom<i128> + TryFrom<u128>>(v: &Value, expect: &str) -> Result<T, Error> {
+    v.to_i128()
+        .and_then(|i| T::try_from(i).ok())
+        .or_else(|| v.to_u128().and_then(|u| T::try_from(u).ok()))
+        .ok_or_else(|| Error(anyhow!("invalid {}: {:?}", expect, v)))
+}
+
+/// Take the inner value of `Value::Shared`, cloning it only if it's still shared elsewhere.
+fn unwrap_shared(v: Arc<Value>) -> Value {
+    Arc::try_unwrap(v).unwrap_or_else(|v| (*v).clone())
+}
+
+/// Present bytes as a seq of `Value::U8`.
+fn bytes_to_seq(v: Vec<u8>) -> Vec<Value> {
+    v.into_iter().map(Value::U8).collect()
+}
+
+/// Order values of a map with `Value::Str` keys `"0".."n-1"` by their index.
+fn index_map_to_seq(m: IndexMap<Value, Value>) -> Result<Vec<Value>, Error> {
+    let mut elements = vec![None; m.len()];
+    for (k, v) in m {
+        let idx = match &k {
+            Value::Str(s) => crate::path::parse_index(s).filter(|idx| *idx < elements.len()),
+            _ => None,
         }
+        .ok_or_else(|| Error(anyhow!("invalid tuple index: {:?}", k)))?;
+        elements[idx] = Some(v);
     }
+    // Keys are unique, so every index has been filled.
+    Ok(elements.into_iter().flatten().collect())
 }
-impl<'de> de::MapAccess<'de> for MapAccessor {
-    type Error = Error;
 
-    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
-    where
-        K: DeserializeSeed<'de>,
-    {
-        debug_assert!(
-            self.cache_value.is_none(),
-            "value for the last entry is not deserialized"
-        );
+/// Collect a seq of `Value::U8` into bytes.
+fn seq_to_bytes(v: Vec<Value>) -> Result<Vec<u8>, Error> {
+    v.into_iter()
+        .map(|v| match v {
+            Value::U8(v) => Ok(v),
+            v => Err(Error(anyhow!("invalid type: {:?}, expect u8 of bytes", v))),
+        })
+        .collect()
+}
 
-        match self.entries.next() {
-            None => Ok(None),
-            Some((k, v)) => {
-                self.cache_value = Some(v);
-                Ok(Some(seed.deserialize(Deserializer(k))?))
-            }
+/// Return an error for the first key not in `fields`.
+fn check_unknown_fields<K: AsRef<str>>(
+    keys: impl Iterator<Item = K>,
+    fields: &'static [&'static str],
+) -> Result<(), Error> {
+    for k in keys {
+        if !fields.contains(&k.as_ref()) {
+            return Err(de::Error::unknown_field(k.as_ref(), fields));
         }
     }
+    Ok(())
+}
 
-    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
-    where
-        V: DeserializeSeed<'de>,
+/// Present struct fields as map entries with `Value::Str` keys.
+fn struct_entries(fields: IndexMap<&'static str, Value>) -> impl Iterator<Item = (Value, Value)> {
+    fields
+        .into_iter()
+        .map(|(k, v)| (Value::Str(k.to_string()), v))
+}
+
+/// Key of entries presented by [`MapAccessor`].
+trait MapKey {
+    fn deserialize<'de, S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+        config: Config,
+    ) -> Result<S::Value, Error>;
+
+    /// Token of the key in paths reported by [`from_value_tracked`].
+    fn token(&self) -> Cow<'_, str>;
+}
+
+impl MapKey for Value {
+    fn deserialize<'de, S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+        config: Config,
+    ) -> Result<S::Value, Error> {
+        seed.deserialize(Deserializer(self, config, None))
+    }
+
+    fn token(&self) -> Cow<'_, str> {
+        Cow::Owned(key_to_string(self))
+    }
+}
+
+/// Struct field and variant names are presented as string keys.
+impl MapKey for &'static str {
+    fn deserialize<'de, S: DeserializeSeed<'de>>(
+        self,
+        seed: S,
+        config: Config,
+    ) -> Result<S::Value, Error> {
+        seed.deserialize(NameDeserializer(self, config))
+    }
+
+    fn token(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+/// Deserializer for struct field and variant names.
+///
+/// Names live for `'static`, so identifiers and strings borrow them via
+/// `visit_borrowed_str` instead of allocating a `String` for every field. Other
+/// types are deserialized like `Value::Str`.
+struct NameDeserializer(&'static str, Config);
+
+impl NameDeserializer {
+    fn into_value(self) -> Deserializer {
+        Deserializer(Value::Str(self.0.to_string()), self.1, None)
+    }
+}
+
+/// Forward methods of [`NameDeserializer`] to the `Value::Str` deserializer.
+macro_rules! forward_to_value {
+    ($($method:ident($($arg:ident: $ty:ty),*))*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* vis: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.into_value().$method($($arg,)* vis)
+            }
+        )*
+    };
+}
+
+impl<'de> serde::Deserializer<'de> for NameDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, vis: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        vis.visit_borrowed_str(self.0)
+    }
+
+    fn deserialize_str<V>(self, vis: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        vis.visit_borrowed_str(self.0)
+    }
+
+    fn deserialize_string<V>(self, vis: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        vis.visit_borrowed_str(self.0)
+    }
+
+    fn deserialize_identifier<V>(self, vis: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        vis.visit_borrowed_str(self.0)
+    }
+
+    forward_to_value! {
+        deserialize_bool() deserialize_i8() deserialize_i16() deserialize_i32()
+        deserialize_i64() deserialize_i128() deserialize_u8() deserialize_u16()
+        deserialize_u32() deserialize_u64() deserialize_u128() deserialize_f32()
+        deserialize_f64() deserialize_char() deserialize_bytes() deserialize_byte_buf()
+        deserialize_option() deserialize_unit() deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str) deserialize_seq()
+        deserialize_tuple(len: usize)
+        deserialize_tuple_struct(name: &'static str, len: usize) deserialize_map()
+        deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+        deserialize_ignored_any()
+    }
+}
+
+struct MapAccessor<I> {
+    cache_value: Option<Value>,
+    cache_track: Option<Tracker>,
+    entries: I,
+    config: Config,
+    track: Option<Tracker>,
+}
+
+impl<Key, I> MapAccessor<I>
+where
+    Key: MapKey,
+    I: Iterator<Item = (Key, Value)>,
+{
+    fn new(
+        entries: impl IntoIterator<IntoIter = I>,
+        config: Config,
+        track: Option<Tracker>,
+    ) -> Self {
+        Self {
+            cache_value: None,
+            cache_track: None,
+            entries: entries.into_iter(),
+            config,
+            track,
+        }
+    }
+}
+
+impl<'de, Key, I> de::MapAccess<'de> for MapAccessor<I>
+where
+    Key: MapKey,
+    I: Iterator<Item = (Key, Value)>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.cache_value.is_some() {
+            return Err(Error(anyhow!(
+                "value for the last entry is not deserialized"
+            )));
+        }
+
+        match self.entries.next() {
+            None => Ok(None),
+            Some((k, v)) => {
+                self.cache_value = Some(v);
+                self.cache_track = self.track.as_ref().map(|t| t.child(&k.token()));
+                Ok(Some(k.deserialize(seed, self.config.descend()?)?))
+            }
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
     {
         let value = self
             .cache_value
             .take()
-            .expect("value for current entry is missing");
-        seed.deserialize(Deserializer(value))
+            .ok_or_else(|| Error(anyhow!("value for current entry is missing")))?;
+        seed.deserialize(Deserializer(
+            value,
+            self.config.descend()?,
+            self.cache_track.take(),
+        ))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        match self.entries.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower),
+            _ => None,
+        }
     }
 }
 
@@ -751,14 +1640,24 @@ struct EnumAccessor {
     name: &'static str,
     variants: &'static [&'static str],
     value: Value,
+    config: Config,
+    track: Option<Tracker>,
 }
 
 impl EnumAccessor {
-    fn new(name: &'static str, variants: &'static [&'static str], value: Value) -> Self {
+    fn new(
+        name: &'static str,
+        variants: &'static [&'static str],
+        value: Value,
+        config: Config,
+        track: Option<Tracker>,
+    ) -> Self {
         Self {
             name,
             variants,
             value,
+            config,
+            track,
         }
     }
 }
@@ -776,47 +1675,91 @@ impl<'de> de::EnumAccess<'de> for EnumAccessor {
                 name: vn,
                 variant_index: vvi,
                 variant: vv,
-            } if &self.name == vn && &self.variants[*vvi as usize] == vv => {
-                seed.deserialize(Deserializer(Value::Str(vv.to_string())))?
             }
-            Value::TupleVariant {
+            | Value::TupleVariant {
                 name: vn,
                 variant_index: vvi,
                 variant: vv,
                 ..
-            } if &self.name == vn && &self.variants[*vvi as usize] == vv => {
-                seed.deserialize(Deserializer(Value::Str(vv.to_string())))?
             }
-            Value::StructVariant {
+            | Value::StructVariant {
                 name: vn,
                 variant_index: vvi,
                 variant: vv,
                 ..
-            } if &self.name == vn && &self.variants[*vvi as usize] == vv => {
-                seed.deserialize(Deserializer(Value::Str(vv.to_string())))?
             }
-            Value::NewtypeVariant {
+            | Value::NewtypeVariant {
                 name: vn,
                 variant_index: vvi,
                 variant: vv,
                 ..
-            } if &self.name == vn && &self.variants[*vvi as usize] == vv => {
-                seed.deserialize(Deserializer(Value::Str(vv.to_string())))?
+            } if &self.name == vn => {
+                // Persisted values may carry indexes of an older version of the enum.
+                if self.variants.get(*vvi as usize) != Some(vv) {
+                    return Err(Error(anyhow!(
+                        "variant `{}` of enum `{}` has stale index {}, use `Value::fix_variant_indices` to update it",
+                        vv,
+                        vn,
+                        vvi
+                    )));
+                }
+                seed.deserialize(Deserializer(Value::Str(vv.to_string()), self.config, None))?
+            }
+            // Unit variants from self-describing formats like JSON are plain strings,
+            // for example the tag of adjacently tagged enums.
+            Value::Str(vv) => {
+                seed.deserialize(Deserializer(Value::Str(vv.clone()), self.config, None))?
+            }
+            // Other variants from self-describing formats are single entry maps
+            // like `{"V": payload}`.
+            Value::Map(m) if m.len() == 1 && matches!(m.first(), Some((Value::Str(_), _))) => {
+                let (k, payload) = match self.value {
+                    Value::Map(m) => m.into_iter().next().expect("must have one entry"),
+                    _ => unreachable!("must be map"),
+                };
+                let track = self.track.map(|t| t.child(&key_to_string(&k)));
+                let value = seed.deserialize(Deserializer(k, self.config, None))?;
+                return Ok((value, VariantAccessor::payload(payload, self.config, track)));
             }
             _ => return Err(Error(anyhow!("invalid type"))),
         };
 
-        Ok((value, VariantAccessor::new(self.value)))
+        Ok((
+            value,
+            VariantAccessor::new(self.value, self.config, self.track),
+        ))
     }
 }
 
 struct VariantAccessor {
     value: Value,
+    config: Config,
+    track: Option<Tracker>,
+    /// `value` is the payload of a single entry map instead of a variant.
+    payload: bool,
 }
 
 impl VariantAccessor {
-    fn new(value: Value) -> Self {
-        Self { value }
+    fn new(value: Value, config: Config, track: Option<Tracker>) -> Self {
+        Self {
+            value,
+            config,
+            track,
+            payload: false,
+        }
+    }
+
+    fn payload(value: Value, config: Config, track: Option<Tracker>) -> Self {
+        Self {
+            value,
+            config,
+            track,
+            payload: true,
+        }
+    }
+
+    fn into_deserializer(self) -> Result<Deserializer, Error> {
+        Ok(Deserializer(self.value, self.config.descend()?, self.track))
     }
 }
 
@@ -824,8 +1767,11 @@ impl<'de> de::VariantAccess<'de> for VariantAccessor {
     type Error = Error;
 
     fn unit_variant(self) -> Result<(), Self::Error> {
+        if self.payload {
+            return de::Deserialize::deserialize(self.into_deserializer()?);
+        }
         match self.value {
-            Value::UnitVariant { .. } => Ok(()),
+            Value::UnitVariant { .. } | Value::Str(_) => Ok(()),
             _ => Err(Error(anyhow!("invalid type"))),
         }
     }
@@ -834,8 +1780,13 @@ impl<'de> de::VariantAccess<'de> for VariantAccessor {
     where
         T: DeserializeSeed<'de>,
     {
+        if self.payload {
+            return seed.deserialize(self.into_deserializer()?);
+        }
         match self.value {
-            Value::NewtypeVariant { value, .. } => Ok(seed.deserialize(Deserializer(*value))?),
+            Value::NewtypeVariant { value, .. } => {
+                Ok(seed.deserialize(Deserializer(*value, self.config.descend()?, self.track))?)
+            }
             _ => Err(Error(anyhow!("invalid type"))),
         }
     }
@@ -844,9 +1795,12 @@ impl<'de> de::VariantAccess<'de> for VariantAccessor {
     where
         V: Visitor<'de>,
     {
+        if self.payload {
+            return de::Deserializer::deserialize_tuple(self.into_deserializer()?, len, vis);
+        }
         match self.value {
             Value::TupleVariant { fields, .. } if len == fields.len() => {
-                vis.visit_seq(SeqAccessor::new(fields))
+                vis.visit_seq(SeqAccessor::new(fields, self.config, self.track))
             }
             _ => Err(Error(anyhow!("invalid type"))),
         }
@@ -860,19 +1814,20 @@ impl<'de> de::VariantAccess<'de> for VariantAccessor {
     where
         V: Visitor<'de>,
     {
+        if self.payload {
+            return de::Deserializer::deserialize_struct(
+                self.into_deserializer()?,
+                "",
+                fields,
+                vis,
+            );
+        }
         match self.value {
-            Value::Struct(_, mut vf) => {
-                let mut vs = Vec::with_capacity(fields.len());
-                for key in fields {
-                    // Use `remove` instead of `get` & `clone` here.
-                    // - As serde will make sure to not access the same field twice.
-                    // - The order of key is not needed to preserve during deserialize.
-                    match vf.remove(key) {
-                        Some(v) => vs.push(v),
-                        None => return Err(Error(anyhow!("field not exist"))),
-                    }
+            Value::StructVariant { fields: vf, .. } => {
+                if self.config.deny_unknown_fields {
+                    check_unknown_fields(vf.keys().copied(), fields)?;
                 }
-                vis.visit_seq(SeqAccessor::new(vs))
+                vis.visit_map(MapAccessor::new(vf, self.config, self.track))
             }
             _ => Err(Error(anyhow!("invalid type"))),
         }
@@ -881,6 +1836,8 @@ impl<'de> de::VariantAccess<'de> for VariantAccessor {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use anyhow::Result;
     use indexmap::indexmap;
 
@@ -941,4 +1898,1128 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_deserialize_seq_iter() -> Result<()> {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+        #[derive(Debug, PartialEq)]
+        struct Counted(i32);
+
+        impl<'de> Deserialize<'de> for Counted {
+            fn deserialize<D>(d: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                COUNT.fetch_add(1, Ordering::SeqCst);
+                Ok(Counted(i32::deserialize(d)?))
+            }
+        }
+
+        let v = Value::Seq((0..10).map(Value::I32).collect());
+        let first = v
+            .deserialize_seq_iter::<Counted>()
+            .take(2)
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(first, vec![Counted(0), Counted(1)]);
+        assert_eq!(COUNT.load(Ordering::SeqCst), 2);
+
+        let v = Value::Seq(vec![Value::I32(1), Value::Str("x".to_string())]);
+        let err = v
+            .deserialize_seq_iter::<i32>()
+            .collect::<Result<Vec<_>, _>>()
+            .expect_err("must fail");
+        assert!(err.to_string().starts_with("element 1: "), "{}", err);
+
+        assert!(Value::Bool(true)
+            .deserialize_seq_iter::<i32>()
+            .next()
+            .expect("must have error")
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_flatten() -> Result<()> {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Deep {
+            z: bool,
+        }
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Inner {
+            x: i32,
+            deep: Deep,
+        }
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Outer {
+            a: bool,
+            #[serde(flatten)]
+            inner: Inner,
+        }
+
+        let raw = Outer {
+            a: true,
+            inner: Inner {
+                x: 1,
+                deep: Deep { z: false },
+            },
+        };
+        let value = crate::into_value(&raw)?;
+        assert_eq!(Outer::from_value(value)?, raw);
+
+        // Flattened struct fields stored as `Value::Struct` should work too.
+        let value = Value::Struct(
+            "Outer",
+            indexmap! {
+                "a" => Value::Bool(true),
+                "x" => Value::I32(1),
+                "deep" => Value::Struct("Deep", indexmap! {"z" => Value::Bool(false)}),
+            },
+        );
+        assert_eq!(Outer::from_value(value)?, raw);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_struct_variant() -> Result<()> {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        enum E {
+            S { r: u8, g: u8 },
+        }
+
+        let raw = E::S { r: 1, g: 2 };
+        assert_eq!(E::from_value(crate::into_value(&raw)?)?, raw);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_with_non_string_keys() -> Result<()> {
+        let v = Value::Map(indexmap! {
+            Value::U64(2) => Value::Str("b".to_string()),
+            Value::U64(1) => Value::Str("a".to_string()),
+        });
+
+        let m: BTreeMap<u64, String> = from_value(v.clone())?;
+        assert_eq!(
+            m,
+            BTreeMap::from([(1, "a".to_string()), (2, "b".to_string())])
+        );
+
+        // Keys are narrowed like any other integer.
+        let m: BTreeMap<u8, String> = from_value(v.clone())?;
+        assert_eq!(m.len(), 2);
+        let err = from_value::<BTreeMap<u64, String>>(Value::Map(indexmap! {
+            Value::I64(-1) => Value::Str("a".to_string()),
+        }))
+        .unwrap_err();
+        assert!(err.to_string().contains("u64"), "{err}");
+
+        // Keys of any kind are kept with `Value` as key type.
+        let mixed = Value::Map(indexmap! {
+            Value::U64(1) => Value::Bool(true),
+            Value::Tuple(vec![Value::I32(1), Value::Str("x".to_string())]) => Value::Unit,
+        });
+        let m: BTreeMap<Value, Value> = from_value(mixed)?;
+        assert_eq!(m[&Value::U64(1)], Value::Bool(true));
+        // Tuples go through `deserialize_any` which only knows about seqs.
+        assert_eq!(
+            m[&Value::Seq(vec![Value::I32(1), Value::Str("x".to_string())])],
+            Value::Unit
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_map_from_struct() -> Result<()> {
+        let v: BTreeMap<String, Value> = from_value(Value::Struct(
+            "TestStruct",
+            indexmap! {
+                "b" => Value::I32(1),
+                "a" => Value::Bool(true),
+            },
+        ))?;
+        assert_eq!(
+            v,
+            BTreeMap::from([
+                ("a".to_string(), Value::Bool(true)),
+                ("b".to_string(), Value::I32(1)),
+            ])
+        );
+
+        let v: BTreeMap<String, i32> = from_value(Value::StructVariant {
+            name: "E",
+            variant_index: 0,
+            variant: "S",
+            fields: indexmap! { "r" => Value::I32(1) },
+        })?;
+        assert_eq!(v, BTreeMap::from([("r".to_string(), 1)]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_struct_from_seq() -> Result<()> {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Row {
+            a: i32,
+            b: String,
+            c: Option<bool>,
+        }
+
+        let v: Row = from_value(Value::Seq(vec![
+            Value::I32(1),
+            Value::Str("x".to_string()),
+            Value::Some(Box::new(Value::Bool(true))),
+        ]))?;
+        assert_eq!(
+            v,
+            Row {
+                a: 1,
+                b: "x".to_string(),
+                c: Some(true)
+            }
+        );
+
+        let short = Value::Seq(vec![Value::I32(1), Value::Str("x".to_string())]);
+        assert!(from_value::<Row>(short.clone()).is_err());
+
+        let v: Row = from_value_with_config(short, Config::default().allow_short_seq(true))?;
+        assert_eq!(
+            v,
+            Row {
+                a: 1,
+                b: "x".to_string(),
+                c: None
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_bytes_into_array() -> Result<()> {
+        let v: [u8; 3] = from_value(Value::Bytes(vec![1, 2, 3]))?;
+        assert_eq!(v, [1, 2, 3]);
+
+        let v: Vec<u8> = from_value(Value::Bytes(vec![1, 2, 3]))?;
+        assert_eq!(v, vec![1, 2, 3]);
+
+        assert!(from_value::<[u8; 2]>(Value::Bytes(vec![1, 2, 3])).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_adjacently_tagged_enum() -> Result<()> {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        #[serde(tag = "t", content = "c")]
+        enum E {
+            A,
+            B(u8),
+            C { x: u8 },
+        }
+
+        for raw in [E::A, E::B(1), E::C { x: 2 }] {
+            // Round trip via `into_value`.
+            assert_eq!(E::from_value(crate::into_value(&raw)?)?, raw);
+
+            // Round trip via JSON.
+            let value: Value = serde_json::from_str(&serde_json::to_string(&raw)?)?;
+            assert_eq!(E::from_value(value)?, raw);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_empty_tuple() -> Result<()> {
+        from_value::<()>(Value::Unit)?;
+        from_value::<()>(Value::Tuple(vec![]))?;
+
+        let v: [u8; 0] = from_value(Value::Tuple(vec![]))?;
+        assert_eq!(v, [0u8; 0]);
+        let v: [u8; 0] = from_value(Value::Seq(vec![]))?;
+        assert_eq!(v, [0u8; 0]);
+
+        assert!(from_value::<[u8; 0]>(Value::Unit).is_err());
+        let v: [u8; 0] =
+            from_value_with_config(Value::Unit, Config::default().unit_as_empty_tuple(true))?;
+        assert_eq!(v, [0u8; 0]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_internally_tagged_enum() -> Result<()> {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Inner {
+            y: String,
+        }
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        #[serde(tag = "type")]
+        enum E {
+            A,
+            B { x: u8 },
+            C(Inner),
+        }
+
+        for raw in [E::A, E::B { x: 1 }, E::C(Inner { y: "y".to_string() })] {
+            // Round trip via `into_value`.
+            assert_eq!(E::from_value(crate::into_value(&raw)?)?, raw);
+
+            // Round trip via JSON.
+            let value: Value = serde_json::from_str(&serde_json::to_string(&raw)?)?;
+            assert_eq!(E::from_value(value)?, raw);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_depth() -> Result<()> {
+        fn nested(depth: usize) -> Value {
+            (0..depth).fold(Value::Unit, |v, _| Value::Some(Box::new(v)))
+        }
+
+        let v: Value = from_value(nested(100))?;
+        assert_eq!(v, nested(100));
+
+        let err = from_value::<Value>(nested(200)).expect_err("must fail");
+        assert!(
+            err.to_string().contains("recursion limit exceeded"),
+            "{}",
+            err
+        );
+
+        let v = Value::Seq(vec![Value::Seq(vec![Value::I32(1)])]);
+        assert!(
+            from_value_with_config::<Vec<Vec<i32>>>(v.clone(), Config::new().max_depth(1)).is_err()
+        );
+        assert_eq!(
+            from_value_with_config::<Vec<Vec<i32>>>(v, Config::new().max_depth(2))?,
+            vec![vec![1]]
+        );
+
+        // Hand-built chains of `Value::Shared` count every level too.
+        fn shared(depth: usize) -> Value {
+            (0..depth).fold(Value::I32(1), |v, _| Value::Shared(Arc::new(v)))
+        }
+        let err = from_value::<i32>(shared(200)).expect_err("must fail");
+        assert!(
+            err.to_string().contains("recursion limit exceeded"),
+            "{}",
+            err
+        );
+        assert!(from_value_with_config::<i32>(shared(2), Config::new().max_depth(1)).is_err());
+        assert_eq!(
+            from_value_with_config::<i32>(shared(2), Config::new().max_depth(2))?,
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_value_tracked() -> Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Server {
+            port: u16,
+        }
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Config {
+            name: String,
+            servers: Vec<Server>,
+        }
+
+        let v = Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Str("web".to_string()),
+            Value::Str("debug".to_string()) => Value::Bool(true),
+            Value::Str("servers".to_string()) => Value::Seq(vec![
+                Value::Map(indexmap! {
+                    Value::Str("port".to_string()) => Value::U16(80),
+                    Value::Str("host/name".to_string()) => Value::Str("a".to_string()),
+                }),
+            ]),
+        });
+
+        let (config, unused): (Config, _) = from_value_tracked(v)?;
+        assert_eq!(
+            config,
+            Config {
+                name: "web".to_string(),
+                servers: vec![Server { port: 80 }],
+            }
+        );
+        assert_eq!(unused, vec!["/debug", "/servers/0/host~1name"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_size_hint() -> Result<()> {
+        /// Collect `size_hint` before every element is consumed.
+        struct Hints(Vec<Option<usize>>);
+
+        impl<'de> Deserialize<'de> for Hints {
+            fn deserialize<D>(d: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                struct HintsVisitor;
+
+                impl<'de> Visitor<'de> for HintsVisitor {
+                    type Value = Hints;
+
+                    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                        write!(f, "seq or map")
+                    }
+
+                    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Hints, A::Error> {
+                        let mut hints = vec![seq.size_hint()];
+                        while seq.next_element::<de::IgnoredAny>()?.is_some() {
+                            hints.push(seq.size_hint());
+                        }
+                        Ok(Hints(hints))
+                    }
+
+                    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Hints, A::Error> {
+                        let mut hints = vec![map.size_hint()];
+                        while map
+                            .next_entry::<de::IgnoredAny, de::IgnoredAny>()?
+                            .is_some()
+                        {
+                            hints.push(map.size_hint());
+                        }
+                        Ok(Hints(hints))
+                    }
+                }
+
+                d.deserialize_any(HintsVisitor)
+            }
+        }
+
+        let v = Value::Seq(vec![Value::U8(1), Value::U8(2), Value::U8(3)]);
+        let hints: Hints = from_value(v)?;
+        assert_eq!(hints.0, vec![Some(3), Some(2), Some(1), Some(0)]);
+
+        let v = Value::Map(indexmap! {
+            Value::U8(1) => Value::U8(1),
+            Value::U8(2) => Value::U8(2),
+        });
+        let hints: Hints = from_value(v)?;
+        assert_eq!(hints.0, vec![Some(2), Some(1), Some(0)]);
+
+        let v: Vec<u64> = from_value(Value::Seq((0..10_000).map(Value::U64).collect()))?;
+        assert_eq!(v.len(), 10_000);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_value_collect_errors() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Config {
+            name: String,
+            port: u16,
+            debug: bool,
+            tags: Option<Vec<String>>,
+        }
+
+        let v = Value::Struct(
+            "Config",
+            indexmap! {
+                "name" => Value::Str("web".to_string()),
+                "port" => Value::I32(-1),
+                "debug" => Value::Str("yes".to_string()),
+            },
+        );
+        let errors: Vec<String> = from_value_collect_errors::<Config>(v)
+            .unwrap_err()
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].starts_with("field `port`: "), "{:?}", errors);
+        assert!(errors[1].starts_with("field `debug`: "), "{:?}", errors);
+
+        let v = Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Str("web".to_string()),
+            Value::Str("port".to_string()) => Value::U16(80),
+            Value::Str("debug".to_string()) => Value::Bool(false),
+        });
+        let config: Config = from_value_collect_errors(v).unwrap();
+        assert_eq!(config.port, 80);
+
+        let errors = from_value_collect_errors::<Config>(Value::Map(IndexMap::new())).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "missing field `name`");
+    }
+
+    #[test]
+    fn test_newtype_struct_without_name() -> Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Millimeters(u8);
+
+        // Newtype structs read from a generic source have an empty name.
+        let v = ValueVisitor.visit_newtype_struct(Deserializer::new(Value::U8(1)))?;
+        assert_eq!(v, Value::NewtypeStruct("", Box::new(Value::U8(1))));
+        assert_eq!(from_value::<Millimeters>(v)?, Millimeters(1));
+
+        // Like structs, newtype structs are matched regardless of name.
+        let v = Value::NewtypeStruct("Meters", Box::new(Value::U8(1)));
+        assert_eq!(from_value::<Millimeters>(v)?, Millimeters(1));
+        let v = Value::NewtypeStruct("Meters", Box::new(Value::Str("1".to_string())));
+        assert!(from_value::<Millimeters>(v).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_enum_from_single_entry_map() -> Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Inner {
+            a: i32,
+            b: String,
+        }
+        #[derive(Deserialize, Debug, PartialEq)]
+        enum E {
+            U,
+            V(Inner),
+            T(i32, i32),
+            S { x: i32 },
+        }
+
+        let v = Value::Map(indexmap! {
+            Value::Str("V".to_string()) => Value::Map(indexmap! {
+                Value::Str("a".to_string()) => Value::I64(1),
+                Value::Str("b".to_string()) => Value::Str("x".to_string()),
+            }),
+        });
+        assert_eq!(
+            from_value::<E>(v)?,
+            E::V(Inner {
+                a: 1,
+                b: "x".to_string()
+            })
+        );
+
+        let v = Value::Map(indexmap! {
+            Value::Str("T".to_string()) => Value::Seq(vec![Value::I64(1), Value::I64(2)]),
+        });
+        assert_eq!(from_value::<E>(v)?, E::T(1, 2));
+        let v = Value::Map(indexmap! {
+            Value::Str("S".to_string()) => Value::Map(indexmap! {
+                Value::Str("x".to_string()) => Value::I64(3),
+            }),
+        });
+        assert_eq!(from_value::<E>(v)?, E::S { x: 3 });
+        let v = Value::Map(indexmap! { Value::Str("U".to_string()) => Value::Unit });
+        assert_eq!(from_value::<E>(v)?, E::U);
+
+        // Round trip through JSON.
+        let v: Value = serde_json::from_str(r#"{"V":{"a":2,"b":"y"}}"#)?;
+        assert_eq!(
+            from_value::<E>(v)?,
+            E::V(Inner {
+                a: 2,
+                b: "y".to_string()
+            })
+        );
+
+        let v = Value::Map(indexmap! { Value::Str("W".to_string()) => Value::Unit });
+        assert!(from_value::<E>(v).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_bytes_from_seq() -> Result<()> {
+        /// Like `serde_bytes::ByteBuf`.
+        #[derive(Debug, PartialEq)]
+        struct ByteBuf(Vec<u8>);
+
+        impl<'de> Deserialize<'de> for ByteBuf {
+            fn deserialize<D: de::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                struct BytesVisitor;
+
+                impl<'de> Visitor<'de> for BytesVisitor {
+                    type Value = ByteBuf;
+
+                    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                        write!(f, "byte array")
+                    }
+
+                    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<ByteBuf, E> {
+                        Ok(ByteBuf(v))
+                    }
+                }
+
+                d.deserialize_byte_buf(BytesVisitor)
+            }
+        }
+
+        let v = Value::Seq(vec![Value::U8(1), Value::U8(2)]);
+        assert_eq!(from_value::<ByteBuf>(v)?, ByteBuf(vec![1, 2]));
+        assert_eq!(
+            from_value::<ByteBuf>(Value::Bytes(vec![3]))?,
+            ByteBuf(vec![3])
+        );
+
+        let v = Value::Seq(vec![Value::U8(1), Value::I32(2)]);
+        let err = from_value::<ByteBuf>(v).unwrap_err();
+        assert_eq!(err.to_string(), "invalid type: I32(2), expect u8 of bytes");
+        Ok(())
+    }
+
+    #[test]
+    fn test_any_bytes() -> Result<()> {
+        /// Only implements `visit_bytes` like most borrowing visitors.
+        #[derive(Debug, PartialEq)]
+        struct AnyBytes(Vec<u8>);
+
+        impl<'de> Deserialize<'de> for AnyBytes {
+            fn deserialize<D: de::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                struct AnyBytesVisitor;
+
+                impl<'de> Visitor<'de> for AnyBytesVisitor {
+                    type Value = AnyBytes;
+
+                    fn expecting(&self, f: &mut Formatter) -> std::fmt::Result {
+                        write!(f, "byte array")
+                    }
+
+                    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<AnyBytes, E> {
+                        Ok(AnyBytes(v.to_vec()))
+                    }
+                }
+
+                d.deserialize_any(AnyBytesVisitor)
+            }
+        }
+
+        assert_eq!(
+            from_value::<AnyBytes>(Value::Bytes(vec![1, 2, 3]))?,
+            AnyBytes(vec![1, 2, 3])
+        );
+        let v: Vec<u8> = from_value(Value::Bytes(vec![1, 2, 3]))?;
+        assert_eq!(v, vec![1, 2, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deny_unknown_fields() -> Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Http {
+            port: u16,
+        }
+        #[derive(Deserialize, Debug, PartialEq)]
+        enum E {
+            S { port: u16 },
+        }
+
+        let strict = Config::new().deny_unknown_fields(true);
+        let s = Value::Struct(
+            "Http",
+            indexmap! { "port" => Value::U16(80), "prot" => Value::U16(81) },
+        );
+        let m = Value::Map(indexmap! {
+            Value::Str("port".to_string()) => Value::U16(80),
+            Value::Str("prot".to_string()) => Value::U16(81),
+        });
+        let sv = Value::StructVariant {
+            name: "E",
+            variant_index: 0,
+            variant: "S",
+            fields: indexmap! { "port" => Value::U16(80), "prot" => Value::U16(81) },
+        };
+
+        // Unknown fields are ignored by default.
+        assert_eq!(from_value::<Http>(s.clone())?, Http { port: 80 });
+        assert_eq!(from_value::<Http>(m.clone())?, Http { port: 80 });
+        assert_eq!(from_value::<E>(sv.clone())?, E::S { port: 80 });
+
+        for v in [s, m] {
+            let err = from_value_with_config::<Http>(v, strict).unwrap_err();
+            assert_eq!(err.to_string(), "unknown field `prot`, expected `port`");
+        }
+        assert!(from_value_with_config::<E>(sv, strict).is_err());
+
+        let v = Value::Map(indexmap! { Value::Str("port".to_string()) => Value::U16(80) });
+        assert_eq!(
+            from_value_with_config::<Http>(v, strict)?,
+            Http { port: 80 }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_untagged_enum() -> Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        #[serde(untagged)]
+        enum E {
+            N(u8),
+            S(String),
+            P { x: i32, y: i32 },
+            L(Vec<E>),
+            U,
+        }
+
+        assert_eq!(from_value::<E>(Value::U8(5))?, E::N(5));
+        assert_eq!(from_value::<E>(Value::I64(5))?, E::N(5));
+        assert_eq!(
+            from_value::<E>(Value::Str("a".to_string()))?,
+            E::S("a".to_string())
+        );
+        assert_eq!(from_value::<E>(Value::Unit)?, E::U);
+
+        let v: Value = serde_json::from_str(r#"[5, "a", {"x": 1, "y": -1}, [7], null]"#)?;
+        assert_eq!(
+            from_value::<Vec<E>>(v)?,
+            vec![
+                E::N(5),
+                E::S("a".to_string()),
+                E::P { x: 1, y: -1 },
+                E::L(vec![E::N(7)]),
+                E::U
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_unit_from_null() -> Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Marker;
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Row {
+            unit: (),
+            marker: Marker,
+        }
+
+        let v: Value = serde_json::from_str("null")?;
+        from_value::<()>(v)?;
+        from_value::<()>(Value::None)?;
+        assert_eq!(from_value::<Marker>(Value::None)?, Marker);
+        assert_eq!(from_value::<Marker>(Value::Unit)?, Marker);
+
+        let v: Value = serde_json::from_str(r#"{"unit": null, "marker": null}"#)?;
+        assert_eq!(
+            from_value::<Row>(v)?,
+            Row {
+                unit: (),
+                marker: Marker
+            }
+        );
+
+        assert!(from_value::<()>(Value::Bool(false)).is_err());
+        assert!(from_value::<Marker>(Value::UnitStruct("Other")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_option_from_self_describing() -> Result<()> {
+        let config = Config::new().option_from_plain(true);
+        assert_eq!(
+            from_value_with_config::<Option<i32>>(Value::Unit, config)?,
+            None
+        );
+        assert_eq!(
+            from_value_with_config::<Option<i32>>(Value::I32(1), config)?,
+            Some(1)
+        );
+
+        let v: Value = serde_json::from_str(r#"[null, 1]"#)?;
+        assert_eq!(
+            from_value_with_config::<Vec<Option<i32>>>(v, config)?,
+            vec![None, Some(1)]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_option_strict() -> Result<()> {
+        assert_eq!(from_value::<Option<i32>>(Value::None)?, None);
+        assert_eq!(
+            from_value::<Option<i32>>(Value::Some(Box::new(Value::I32(1))))?,
+            Some(1)
+        );
+
+        // Only `Value::None` and `Value::Some` are options by default.
+        let err = from_value::<Option<i32>>(Value::Unit).unwrap_err();
+        assert_eq!(err.to_string(), "invalid type: Unit, expect option");
+        assert!(from_value::<Option<i32>>(Value::I32(1)).is_err());
+
+        // Scalar coercion doesn't read plain values as options.
+        let config = Config::new().lenient(true);
+        assert!(from_value_with_config::<Option<i32>>(Value::Unit, config).is_err());
+        assert!(from_value_with_config::<Option<i32>>(Value::I32(1), config).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_nested_option() -> Result<()> {
+        let cases: [(Option<Option<i32>>, Value); 3] = [
+            (None, Value::None),
+            (Some(None), Value::Some(Box::new(Value::None))),
+            (
+                Some(Some(5)),
+                Value::Some(Box::new(Value::Some(Box::new(Value::I32(5))))),
+            ),
+        ];
+        for (expected, v) in cases {
+            assert_eq!(crate::into_value(expected)?, v);
+            assert_eq!(from_value::<Option<Option<i32>>>(v)?, expected);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_borrowed_field_names() -> Result<()> {
+        let fields: Vec<_> = (0..256)
+            .map(|i| (format!("field_{}", i), Value::I32(i)))
+            .collect();
+        let v = Value::new_struct("Large", fields);
+
+        // Field names are lent to the visitor instead of being copied.
+        let m = BTreeMap::<&'static str, i32>::deserialize(Deserializer::new(v.clone()))?;
+        assert_eq!(m.len(), 256);
+        assert_eq!(m["field_255"], 255);
+
+        let m: BTreeMap<String, i32> = from_value(v.clone())?;
+        assert_eq!(m["field_0"], 0);
+
+        // Other key types still go through `Value::Str`.
+        let v = Value::Struct("S", indexmap! { "1" => Value::Bool(true) });
+        let m: BTreeMap<u8, bool> = from_value_with_config(v.clone(), Config::new().lenient(true))?;
+        assert!(m[&1]);
+        assert!(from_value::<BTreeMap<u8, bool>>(v).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_accessor_misuse() -> Result<()> {
+        let entries = indexmap! {
+            Value::Str("a".to_string()) => Value::I32(1),
+            Value::Str("b".to_string()) => Value::I32(2),
+        };
+
+        // Skip the value of the first entry.
+        let mut access = MapAccessor::new(entries.clone(), Config::default(), None);
+        assert_eq!(access.next_key::<String>()?, Some("a".to_string()));
+        let err = access.next_key::<String>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "value for the last entry is not deserialized"
+        );
+
+        // Read a value without reading its key.
+        let mut access = MapAccessor::new(entries, Config::default(), None);
+        let err = access.next_value::<i32>().unwrap_err();
+        assert_eq!(err.to_string(), "value for current entry is missing");
+        assert_eq!(
+            access.next_entry::<String, i32>()?,
+            Some(("a".to_string(), 1))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_tuple_struct_from_seq() -> Result<()> {
+        #[derive(serde::Serialize, Deserialize, Debug, PartialEq)]
+        struct Rgb(u8, u8, u8);
+
+        let v = crate::into_value(Rgb(1, 2, 3))?;
+        assert!(matches!(v, Value::TupleStruct("Rgb", _)));
+
+        let json = serde_json::to_string(&v)?;
+        assert_eq!(json, "[1,2,3]");
+        let v: Value = serde_json::from_str(&json)?;
+        assert_eq!(from_value::<Rgb>(v)?, Rgb(1, 2, 3));
+
+        let v = Value::Tuple(vec![Value::U8(1), Value::U8(2), Value::U8(3)]);
+        assert_eq!(from_value::<Rgb>(v)?, Rgb(1, 2, 3));
+        assert!(from_value::<Rgb>(Value::Seq(vec![Value::U8(1)])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ignored_any() -> Result<()> {
+        let variants = crate::fixtures::all_variants();
+
+        for v in variants.clone() {
+            from_value::<de::IgnoredAny>(v)?;
+        }
+        from_value::<de::IgnoredAny>(Value::Seq(variants))?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_integer_out_of_range() {
+        let err = from_value::<u8>(Value::I32(300)).unwrap_err();
+        assert_eq!(err.to_string(), "value 300 out of range for u8");
+        let err = from_value::<u32>(Value::I8(-1)).unwrap_err();
+        assert_eq!(err.to_string(), "value -1 out of range for u32");
+        let err = from_value::<i64>(Value::U128(u128::MAX)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("value {} out of range for i64", u128::MAX)
+        );
+        let err =
+            from_value::<Vec<i8>>(Value::Seq(vec![Value::I8(1), Value::U8(128)])).unwrap_err();
+        assert_eq!(err.to_string(), "value 128 out of range for i8");
+    }
+
+    #[test]
+    fn test_lenient_char_from_code_point() -> Result<()> {
+        let lenient = Config::new().lenient(true);
+
+        assert_eq!(
+            from_value_with_config::<char>(Value::U32(0x1F600), lenient)?,
+            '😀'
+        );
+        assert_eq!(
+            from_value_with_config::<char>(Value::I64(97), lenient)?,
+            'a'
+        );
+
+        let err = from_value_with_config::<char>(Value::U32(0xD800), lenient).unwrap_err();
+        assert_eq!(err.to_string(), "invalid char: U32(55296)");
+        assert!(from_value_with_config::<char>(Value::U64(0x110000), lenient).is_err());
+        assert!(from_value_with_config::<char>(Value::I32(-1), lenient).is_err());
+        assert!(from_value::<char>(Value::U32(97)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_absent_option_field() -> Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Limits {
+            max: i32,
+            min: Option<i32>,
+        }
+
+        let m = Value::Map(indexmap! { Value::Str("max".to_string()) => Value::I32(10) });
+        assert_eq!(from_value::<Limits>(m)?, Limits { max: 10, min: None });
+        let s = Value::Struct("Limits", indexmap! { "max" => Value::I32(10) });
+        assert_eq!(from_value::<Limits>(s)?, Limits { max: 10, min: None });
+
+        // Required fields are still required.
+        let s = Value::Struct(
+            "Limits",
+            indexmap! { "min" => Value::Some(Box::new(Value::I32(1))) },
+        );
+        let err = from_value::<Limits>(s).unwrap_err();
+        assert_eq!(err.to_string(), "missing field `max`");
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_int_from_float() -> Result<()> {
+        let lenient = Config::new().lenient(true);
+
+        assert_eq!(from_value_with_config::<i64>(Value::F64(4.0), lenient)?, 4);
+        assert_eq!(
+            from_value_with_config::<u8>(Value::F32(255.0), lenient)?,
+            255
+        );
+        assert_eq!(
+            from_value_with_config::<u128>(Value::F64(1e30), lenient)?,
+            1_000_000_000_000_000_019_884_624_838_656
+        );
+        assert_eq!(from_value_with_config::<i8>(Value::F64(-0.0), lenient)?, 0);
+
+        let err = from_value_with_config::<i64>(Value::F64(4.5), lenient).unwrap_err();
+        assert_eq!(err.to_string(), "invalid i64: F64(4.5)");
+        assert!(from_value_with_config::<u8>(Value::F64(256.0), lenient).is_err());
+        assert!(from_value_with_config::<u8>(Value::F64(-1.0), lenient).is_err());
+        assert!(from_value_with_config::<i32>(Value::F64(f64::NAN), lenient).is_err());
+
+        // Floats are rejected without lenient.
+        assert!(from_value::<i64>(Value::F64(4.0)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tuple_from_index_map() -> Result<()> {
+        let v = Value::Map(indexmap! {
+            Value::Str("1".to_string()) => Value::I32(2),
+            Value::Str("0".to_string()) => Value::I32(1),
+        });
+        let lenient = Config::new().lenient(true);
+
+        let t: (i32, i32) = from_value_with_config(v.clone(), lenient)?;
+        assert_eq!(t, (1, 2));
+        assert!(from_value::<(i32, i32)>(v.clone()).is_err());
+        assert!(from_value_with_config::<(i32, i32, i32)>(v, lenient).is_err());
+
+        let v = Value::Map(indexmap! {
+            Value::Str("0".to_string()) => Value::I32(1),
+            Value::Str("2".to_string()) => Value::I32(2),
+        });
+        let err = from_value_with_config::<(i32, i32)>(v, lenient).unwrap_err();
+        assert_eq!(err.to_string(), r#"invalid tuple index: Str("2")"#);
+
+        let v = Value::Map(indexmap! {
+            Value::Str("0".to_string()) => Value::I32(1),
+            Value::Str("+1".to_string()) => Value::I32(2),
+        });
+        assert!(from_value_with_config::<(i32, i32)>(v, lenient).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_lenient_float() -> Result<()> {
+        let config = Config::new().lenient(true);
+        let parse = |v: &str| from_value_with_config::<f64>(Value::Str(v.to_string()), config);
+
+        assert_eq!(parse("2.5")?, 2.5);
+        assert_eq!(parse("-1e3")?, -1000.0);
+        assert!(parse("NaN")?.is_nan());
+        assert_eq!(parse("inf")?, f64::INFINITY);
+        assert_eq!(parse("-Infinity")?, f64::NEG_INFINITY);
+        assert_eq!(parse("3.14")?.to_string(), "3.14");
+        let err = parse("abc").unwrap_err();
+        assert_eq!(err.to_string(), r#"invalid f64: "abc""#);
+        assert!(parse(" 1").is_err());
+
+        let v: f32 = from_value_with_config(Value::Str("0.5".to_string()), config)?;
+        assert_eq!(v, 0.5);
+
+        // Strict mode rejects strings.
+        assert!(from_value::<f64>(Value::Str("2.5".to_string())).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_shared() -> Result<()> {
+        let shared = Value::Str("a".to_string()).shared();
+        let v = Value::Map(indexmap! {
+            Value::U8(1).shared() => Value::Some(Box::new(shared.clone())),
+            Value::U8(2) => Value::Seq(vec![shared.clone(), Value::None.shared()]).shared(),
+        });
+
+        let m: BTreeMap<u8, Value> = from_value(v.clone())?;
+        assert_eq!(m[&1], Value::Some(Box::new(Value::Str("a".to_string()))));
+        assert_eq!(
+            m[&2],
+            Value::Seq(vec![Value::Str("a".to_string()), Value::None])
+        );
+
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        enum E {
+            A,
+            B(u8),
+        }
+        let es: Vec<E> = from_value(Value::Seq(vec![
+            Value::Str("A".to_string()).shared(),
+            crate::into_value(E::B(1))?.shared(),
+        ]))?;
+        assert_eq!(es, vec![E::A, E::B(1)]);
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct S {
+            a: Option<String>,
+            b: Vec<Option<String>>,
+        }
+        let s: S = from_value(Value::Map(indexmap! {
+            Value::Str("a".to_string()).shared() => Value::Some(Box::new(shared.clone())),
+            Value::Str("b".to_string()) => Value::Seq(vec![Value::Some(Box::new(shared)), Value::None.shared()]).shared(),
+        }))?;
+        assert_eq!(
+            s,
+            S {
+                a: Some("a".to_string()),
+                b: vec![Some("a".to_string()), None],
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_exact_f32() -> Result<()> {
+        let config = Config::new().exact_f32(true);
+
+        let err = from_value_with_config::<f32>(Value::F64(0.1), config).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "f64 0.1 can't be represented as f32 exactly"
+        );
+        assert!(from_value_with_config::<f32>(Value::F64(1e300), config).is_err());
+        assert_eq!(from_value_with_config::<f32>(Value::F64(0.5), config)?, 0.5);
+        assert_eq!(from_value_with_config::<f32>(Value::F32(0.1), config)?, 0.1);
+        let v: f32 = from_value_with_config(Value::F64(f64::NAN), config)?;
+        assert!(v.is_nan());
+
+        // Narrowing is lossy by default.
+        assert_eq!(from_value::<f32>(Value::F64(0.1))?, 0.1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_value_in_place() -> Result<()> {
+        let mut target: Vec<u64> = Vec::with_capacity(16);
+        target.extend([1, 2, 3]);
+        let ptr = target.as_ptr();
+
+        from_value_in_place(Value::Seq(vec![Value::U64(4), Value::U64(5)]), &mut target)?;
+        assert_eq!(target, vec![4, 5]);
+        from_value_in_place(Value::Seq((0..10).map(Value::U64).collect()), &mut target)?;
+        assert_eq!(target, (0..10).collect::<Vec<_>>());
+        // The buffer is reused since it has enough capacity.
+        assert_eq!(target.as_ptr(), ptr);
+
+        let mut target = TestStruct {
+            a: false,
+            b: 0,
+            c: 0,
+            d: String::new(),
+            e: 0.0,
+        };
+        from_value_in_place(
+            Value::Struct(
+                "TestStruct",
+                indexmap! {
+                    "a" => Value::Bool(true),
+                    "b" => Value::I32(1),
+                    "c" => Value::U64(2),
+                    "d" => Value::Str("x".to_string()),
+                    "e" => Value::F64(3.5),
+                },
+            ),
+            &mut target,
+        )?;
+        assert_eq!(target.d, "x");
+        assert_eq!(target.e, 3.5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deserialize_flatten_catch_all() -> Result<()> {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Config {
+            name: String,
+            #[serde(flatten)]
+            rest: std::collections::HashMap<String, Value>,
+        }
+
+        let value = Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Str("web".to_string()),
+            Value::Str("port".to_string()) => Value::U16(80),
+            Value::Str("tags".to_string()) => Value::Seq(vec![Value::Str("a".to_string())]),
+        });
+        let config = Config::from_value(value)?;
+
+        assert_eq!(config.name, "web");
+        assert_eq!(config.rest.len(), 2);
+        assert_eq!(config.rest["port"], Value::U16(80));
+        assert_eq!(
+            config.rest["tags"],
+            Value::Seq(vec![Value::Str("a".to_string())])
+        );
+        Ok(())
+    }
 }
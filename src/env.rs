@@ -0,0 +1,299 @@
+use anyhow::anyhow;
+use indexmap::IndexMap;
+
+use crate::transform::key_to_string;
+use crate::{Error, Value};
+
+/// Normalize a key segment for environment variable names.
+///
+/// ASCII letters are uppercased, digits are kept and every other char is replaced by `_`.
+fn env_key(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+impl Value {
+    /// Flatten value into environment variable style `(KEY, value)` pairs.
+    ///
+    /// - Keys of nested maps/structs are joined with `_` after `prefix`, for example
+    ///   `PREFIX_SECTION_KEY`. An empty `prefix` adds no leading `_`.
+    /// - Elements of seqs/tuples use their index as key, for example `PREFIX_PORTS_0`.
+    /// - Key segments are uppercased, chars other than ASCII letters and digits are
+    ///   replaced by `_`.
+    /// - `Some`, newtype structs and newtype variants are transparent.
+    /// - Scalars use their display form, bytes are converted via lossy UTF-8,
+    ///   `None`/`Unit`/unit structs become an empty string and unit variants use
+    ///   the variant name.
+    /// - Empty containers produce no pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::indexmap;
+    /// use serde_bridge::Value;
+    ///
+    /// let v = Value::Struct("Config", indexmap! {
+    ///     "log" => Value::Struct("Log", indexmap! { "level" => Value::Str("info".to_string()) }),
+    /// });
+    /// assert_eq!(
+    ///     v.to_env_pairs("app"),
+    ///     vec![("APP_LOG_LEVEL".to_string(), "info".to_string())]
+    /// );
+    /// ```
+    pub fn to_env_pairs(&self, prefix: &str) -> Vec<(String, String)> {
+        let mut pairs = Vec::new();
+        self.collect_env_pairs(env_key(prefix), &mut pairs);
+        pairs
+    }
+
+    fn collect_env_pairs(&self, key: String, pairs: &mut Vec<(String, String)>) {
+        let join = |k: &str| {
+            if key.is_empty() {
+                env_key(k)
+            } else {
+                format!("{}_{}", key, env_key(k))
+            }
+        };
+
+        match self {
+            Value::Some(v) | Value::NewtypeStruct(_, v) => v.collect_env_pairs(key, pairs),
+            Value::NewtypeVariant { value, .. } => value.collect_env_pairs(key, pairs),
+            Value::Shared(v) => v.collect_env_pairs(key, pairs),
+            Value::Seq(v)
+            | Value::Tuple(v)
+            | Value::TupleStruct(_, v)
+            | Value::TupleVariant { fields: v, .. } => {
+                for (idx, v) in v.iter().enumerate() {
+                    v.collect_env_pairs(join(&idx.to_string()), pairs)
+                }
+            }
+            Value::Map(m) => {
+                for (k, v) in m {
+                    v.collect_env_pairs(join(&key_to_string(k)), pairs)
+                }
+            }
+            Value::Struct(_, fields) | Value::StructVariant { fields, .. } => {
+                for (k, v) in fields {
+                    v.collect_env_pairs(join(k), pairs)
+                }
+            }
+            Value::Bytes(v) => pairs.push((key, String::from_utf8_lossy(v).to_string())),
+            Value::None | Value::Unit | Value::UnitStruct(_) => pairs.push((key, String::new())),
+            v => pairs.push((key, key_to_string(v))),
+        }
+    }
+}
+
+impl Value {
+    /// Build a nested `Value::Map` from environment variable style `(KEY, value)` pairs.
+    ///
+    /// - Pairs whose key doesn't start with `prefix` followed by `separator` are skipped.
+    ///   An empty `prefix` keeps all pairs.
+    /// - The rest of the key is split by `separator` and every segment is lowercased,
+    ///   so `APP_LOG_LEVEL=info` with prefix `APP` becomes `{"log": {"level": "info"}}`.
+    /// - Maps with index keys `0..n` become seqs like [`Value::from_flat_map`], so
+    ///   `APP_PEERS_0` and `APP_PEERS_1` become `{"peers": [..]}`.
+    /// - Values are kept as `Value::Str`, deserialize with [`Config::lenient`] to
+    ///   parse them into numbers and bools.
+    ///
+    /// # Collisions
+    ///
+    /// - The same key appearing twice: the later value wins.
+    /// - A key that is both a value and a prefix of other keys (`APP_LOG` and
+    ///   `APP_LOG_LEVEL`): returns an error.
+    /// - Keys with empty segments (`APP__LOG`): returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let v = Value::from_env_pairs(
+    ///     vec![("APP_LOG_LEVEL".to_string(), "info".to_string())],
+    ///     "APP",
+    ///     "_",
+    /// )?;
+    /// assert_eq!(v.pointer("/log/level"), Some(&Value::Str("info".to_string())));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`Config::lenient`]: crate::Config::lenient
+    pub fn from_env_pairs(
+        pairs: impl IntoIterator<Item = (String, String)>,
+        prefix: &str,
+        separator: &str,
+    ) -> Result<Value, Error> {
+        let mut flat = IndexMap::new();
+
+        for (key, value) in pairs {
+            let rest = if prefix.is_empty() {
+                key.as_str()
+            } else {
+                match key
+                    .strip_prefix(prefix)
+                    .and_then(|k| k.strip_prefix(separator))
+                {
+                    Some(rest) => rest,
+                    None => continue,
+                }
+            };
+            if rest.is_empty() {
+                return Err(Error(anyhow!("env key `{}` contains empty segment", key)));
+            }
+
+            flat.insert(rest.to_lowercase(), Value::Str(value));
+        }
+
+        Value::from_flat_map(flat, separator).map_err(|e| Error(anyhow!("parse env: {}", e)))
+    }
+
+    /// Build a nested `Value::Map` from environment variables of current process.
+    ///
+    /// Variables are handled like [`Value::from_env_pairs`], variables whose name or
+    /// value is not valid unicode are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use serde_bridge::{from_value_with_config, Config, Value};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// #[derive(serde::Deserialize)]
+    /// struct Http {
+    ///     port: u16,
+    /// }
+    ///
+    /// // APP_PORT=8080
+    /// let v = Value::from_env("APP", "_")?;
+    /// let http: Http = from_value_with_config(v, Config::new().lenient(true))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_env(prefix: &str, separator: &str) -> Result<Value, Error> {
+        Value::from_env_pairs(
+            std::env::vars_os()
+                .filter_map(|(k, v)| Some((k.into_string().ok()?, v.into_string().ok()?))),
+            prefix,
+            separator,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_to_env_pairs() {
+        let v = Value::Struct(
+            "Config",
+            indexmap! {
+                "name" => Value::Str("demo".to_string()),
+                "http" => Value::Struct("Http", indexmap! {
+                    "listen-port" => Value::U16(8080),
+                    "tls" => Value::None,
+                }),
+                "peers" => Value::Seq(vec![Value::Str("a".to_string()), Value::Str("b".to_string())]),
+                "debug" => Value::Some(Box::new(Value::Bool(true))),
+            },
+        );
+
+        assert_eq!(
+            v.to_env_pairs("app"),
+            vec![
+                ("APP_NAME".to_string(), "demo".to_string()),
+                ("APP_HTTP_LISTEN_PORT".to_string(), "8080".to_string()),
+                ("APP_HTTP_TLS".to_string(), "".to_string()),
+                ("APP_PEERS_0".to_string(), "a".to_string()),
+                ("APP_PEERS_1".to_string(), "b".to_string()),
+                ("APP_DEBUG".to_string(), "true".to_string()),
+            ]
+        );
+        assert_eq!(
+            Value::Map(indexmap! { Value::Str("a".to_string()) => Value::I32(1) }).to_env_pairs(""),
+            vec![("A".to_string(), "1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_from_env_pairs() -> anyhow::Result<()> {
+        let pairs = vec![
+            ("APP_NAME".to_string(), "demo".to_string()),
+            ("APP_HTTP_PORT".to_string(), "8080".to_string()),
+            ("APP_HTTP_HOST".to_string(), "localhost".to_string()),
+            ("OTHER_NAME".to_string(), "ignored".to_string()),
+        ];
+
+        let v = Value::from_env_pairs(pairs, "APP", "_")?;
+        assert_eq!(
+            v,
+            Value::Map(indexmap! {
+                Value::Str("name".to_string()) => Value::Str("demo".to_string()),
+                Value::Str("http".to_string()) => Value::Map(indexmap! {
+                    Value::Str("port".to_string()) => Value::Str("8080".to_string()),
+                    Value::Str("host".to_string()) => Value::Str("localhost".to_string()),
+                }),
+            })
+        );
+
+        let pairs = vec![
+            ("APP_LOG".to_string(), "x".to_string()),
+            ("APP_LOG_LEVEL".to_string(), "info".to_string()),
+        ];
+        assert!(Value::from_env_pairs(pairs, "APP", "_").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_env_pairs_into_struct() -> anyhow::Result<()> {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Http {
+            port: u16,
+            tls: bool,
+        }
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Config {
+            name: String,
+            http: Http,
+            peers: Vec<String>,
+            ratio: f64,
+        }
+
+        let pairs = vec![
+            ("APP_NAME".to_string(), "demo".to_string()),
+            ("APP_HTTP_PORT".to_string(), "8080".to_string()),
+            ("APP_HTTP_TLS".to_string(), "true".to_string()),
+            ("APP_PEERS_1".to_string(), "b".to_string()),
+            ("APP_PEERS_0".to_string(), "a".to_string()),
+            ("APP_RATIO".to_string(), "0.5".to_string()),
+        ];
+        let v = Value::from_env_pairs(pairs, "APP", "_")?;
+
+        let config: Config = crate::from_value_with_config(v, crate::Config::new().lenient(true))?;
+        assert_eq!(
+            config,
+            Config {
+                name: "demo".to_string(),
+                http: Http {
+                    port: 8080,
+                    tls: true
+                },
+                peers: vec!["a".to_string(), "b".to_string()],
+                ratio: 0.5,
+            }
+        );
+        Ok(())
+    }
+}
@@ -4,9 +4,28 @@ use std::num::TryFromIntError;
 use anyhow::anyhow;
 use serde::{de, ser};
 
+/// Error returned while converting from/to [`Value`](crate::Value).
 #[derive(Debug)]
 pub struct Error(pub anyhow::Error);
 
+impl Error {
+    /// Wrap the error with a breadcrumb describing where it happens.
+    pub(crate) fn context<C>(self, ctx: C) -> Self
+    where
+        C: Display + Send + Sync + 'static,
+    {
+        Error(self.0.context(ctx))
+    }
+}
+
+/// Cloned errors keep the [`Display`] output, but the source chain is flattened
+/// into a single message, so downcasting the inner `anyhow::Error` won't work.
+impl Clone for Error {
+    fn clone(&self) -> Self {
+        Error(anyhow!("{:#}", self.0))
+    }
+}
+
 impl ser::Error for Error {
     fn custom<T: Display>(msg: T) -> Self {
         Error(anyhow!("{}", msg))
@@ -21,7 +40,8 @@ impl de::Error for Error {
 
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        Display::fmt(&self.0, f)
+        // Use alternate format so that the whole breadcrumb trail is displayed.
+        write!(f, "{:#}", self.0)
     }
 }
 
@@ -32,3 +52,19 @@ impl From<TryFromIntError> for Error {
         Error(anyhow::anyhow!("convert from int: {:?}", v))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clone() {
+        let err = Error(anyhow!("invalid type")).context("deserialize field `a`");
+        let cloned = err.clone();
+        assert_eq!(cloned.to_string(), err.to_string());
+        assert_eq!(cloned.to_string(), "deserialize field `a`: invalid type");
+
+        let errors = vec![err];
+        assert_eq!(errors.clone()[0].to_string(), errors[0].to_string());
+    }
+}
@@ -0,0 +1,84 @@
+use indexmap::indexmap;
+use serde::{Deserialize, Serialize};
+
+use crate::Value;
+
+/// One value of every variant of [`Value`] except `Shared`, in declaration order.
+pub(crate) fn all_variants() -> Vec<Value> {
+    vec![
+        Value::Bool(true),
+        Value::I8(-1),
+        Value::I16(-2),
+        Value::I32(-3),
+        Value::I64(-4),
+        Value::I128(-5),
+        Value::U8(1),
+        Value::U16(2),
+        Value::U32(3),
+        Value::U64(4),
+        Value::U128(5),
+        Value::F32(1.5),
+        Value::F64(2.5),
+        Value::Char('c'),
+        Value::Str("s".to_string()),
+        Value::Bytes(vec![1, 2]),
+        Value::None,
+        Value::Some(Box::new(Value::Bool(false))),
+        Value::Unit,
+        Value::UnitStruct("Unit"),
+        Value::UnitVariant {
+            name: "E",
+            variant_index: 0,
+            variant: "A",
+        },
+        Value::NewtypeStruct("N", Box::new(Value::U8(1))),
+        Value::NewtypeVariant {
+            name: "E",
+            variant_index: 1,
+            variant: "N",
+            value: Box::new(Value::U8(1)),
+        },
+        Value::Tuple(vec![Value::U8(1), Value::U8(2)]),
+        Value::TupleStruct("T", vec![Value::U8(1)]),
+        Value::TupleVariant {
+            name: "E",
+            variant_index: 2,
+            variant: "T",
+            fields: vec![Value::U8(1), Value::U8(2)],
+        },
+        Value::Map(indexmap! { Value::Str("k".to_string()) => Value::U8(1) }),
+        Value::Struct("S", indexmap! { "r" => Value::U8(1) }),
+        Value::StructVariant {
+            name: "E",
+            variant_index: 3,
+            variant: "S",
+            fields: indexmap! { "r" => Value::U8(1) },
+        },
+    ]
+}
+
+/// JSON of [`all_variants`] as a seq.
+pub(crate) const ALL_VARIANTS_JSON: &str = r#"[true,-1,-2,-3,-4,-5,1,2,3,4,5,1.5,2.5,"c","s",[1,2],null,false,null,null,"A",1,{"N":1},[1,2],[1],{"T":[1,2]},{"k":1},{"r":1},{"S":{"r":1}}]"#;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub(crate) struct TestStruct {
+    pub(crate) a: bool,
+    pub(crate) b: i32,
+    pub(crate) c: u64,
+    pub(crate) d: String,
+    pub(crate) e: f64,
+}
+
+/// JSON of [`test_struct`].
+pub(crate) const TEST_STRUCT_JSON: &str =
+    r#"{"a": true, "b": -1, "c": 2, "d": "Hello, World!", "e": 3.4}"#;
+
+pub(crate) fn test_struct() -> TestStruct {
+    TestStruct {
+        a: true,
+        b: -1,
+        c: 2,
+        d: "Hello, World!".to_string(),
+        e: 3.4,
+    }
+}
@@ -0,0 +1,288 @@
+use anyhow::anyhow;
+use indexmap::map::Entry;
+use indexmap::IndexMap;
+
+use crate::path::parse_index;
+use crate::transform::key_to_string;
+use crate::{Error, Value};
+
+impl Value {
+    /// Flatten nested maps, structs and seqs into a single map keyed by joined paths.
+    ///
+    /// - Keys of nested maps/structs are joined with `separator`, for example `h.a`.
+    /// - Elements of seqs/tuples use their index as key, for example `g.0`.
+    /// - `Some`, newtype structs and newtype variants are transparent.
+    /// - Only scalar leaves appear in the output, including `None` and `Unit`.
+    ///   Empty containers produce no entries.
+    /// - A scalar at the root is stored under the empty key `""`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::indexmap;
+    /// use serde_bridge::Value;
+    ///
+    /// let v = Value::Struct("Config", indexmap! {
+    ///     "ports" => Value::Seq(vec![Value::U16(80), Value::U16(443)]),
+    /// });
+    /// assert_eq!(
+    ///     v.flatten_to_map("."),
+    ///     indexmap! {
+    ///         "ports.0".to_string() => Value::U16(80),
+    ///         "ports.1".to_string() => Value::U16(443),
+    ///     }
+    /// );
+    /// ```
+    pub fn flatten_to_map(&self, separator: &str) -> IndexMap<String, Value> {
+        let mut flat = IndexMap::new();
+        self.collect_flat(String::new(), separator, &mut flat);
+        flat
+    }
+
+    fn collect_flat(&self, key: String, separator: &str, flat: &mut IndexMap<String, Value>) {
+        let join = |k: &str| {
+            if key.is_empty() {
+                k.to_string()
+            } else {
+                format!("{}{}{}", key, separator, k)
+            }
+        };
+
+        match self {
+            Value::Some(v) | Value::NewtypeStruct(_, v) => v.collect_flat(key, separator, flat),
+            Value::NewtypeVariant { value, .. } => value.collect_flat(key, separator, flat),
+            Value::Shared(v) => v.collect_flat(key, separator, flat),
+            Value::Seq(v)
+            | Value::Tuple(v)
+            | Value::TupleStruct(_, v)
+            | Value::TupleVariant { fields: v, .. } => {
+                for (idx, v) in v.iter().enumerate() {
+                    v.collect_flat(join(&idx.to_string()), separator, flat)
+                }
+            }
+            Value::Map(m) => {
+                for (k, v) in m {
+                    v.collect_flat(join(&key_to_string(k)), separator, flat)
+                }
+            }
+            Value::Struct(_, fields) | Value::StructVariant { fields, .. } => {
+                for (k, v) in fields {
+                    v.collect_flat(join(k), separator, flat)
+                }
+            }
+            v => {
+                flat.insert(key, v.clone());
+            }
+        }
+    }
+}
+
+impl Value {
+    /// Build a nested value from a flat map, the inverse of [`Value::flatten_to_map`].
+    ///
+    /// - Keys are split by `separator` into segments of nested `Value::Map`s with
+    ///   `Value::Str` keys, so `h.a` becomes `{"h": {"a": ..}}`.
+    /// - A map whose keys are exactly the indexes `0..n` (without leading zeros)
+    ///   becomes a `Value::Seq` ordered by index.
+    /// - The empty key `""` is the root value, it must be the only entry.
+    ///
+    /// # Errors
+    ///
+    /// - A key that is both a value and a prefix of other keys (`h` and `h.a`).
+    /// - Keys with empty segments (`h..a`) or an empty `separator`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::indexmap;
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let v = Value::from_flat_map(indexmap! { "ports.0".to_string() => Value::U16(80) }, ".")?;
+    /// assert_eq!(v.pointer("/ports"), Some(&Value::Seq(vec![Value::U16(80)])));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_flat_map(map: IndexMap<String, Value>, separator: &str) -> Result<Value, Error> {
+        if separator.is_empty() {
+            return Err(Error(anyhow!("separator must not be empty")));
+        }
+
+        let len = map.len();
+        let mut root = IndexMap::new();
+        for (key, value) in map {
+            if key.is_empty() {
+                if len == 1 {
+                    return Ok(value);
+                }
+                return Err(Error(anyhow!("root value conflicts with nested keys")));
+            }
+
+            let segments: Vec<&str> = key.split(separator).collect();
+            if segments.iter().any(|s| s.is_empty()) {
+                return Err(Error(anyhow!("key `{}` contains empty segment", key)));
+            }
+
+            let (last, parents) = segments.split_last().expect("split must have one segment");
+            let mut current = &mut root;
+            for seg in parents {
+                let entry = current
+                    .entry(Value::Str(seg.to_string()))
+                    .or_insert_with(|| Value::Map(IndexMap::new()));
+                current = match entry {
+                    Value::Map(m) => m,
+                    _ => {
+                        return Err(Error(anyhow!(
+                            "key `{}` conflicts with value at `{}`",
+                            key,
+                            seg
+                        )))
+                    }
+                };
+            }
+
+            match current.entry(Value::Str(last.to_string())) {
+                Entry::Occupied(_) => {
+                    return Err(Error(anyhow!("key `{}` conflicts with nested keys", key)))
+                }
+                Entry::Vacant(e) => {
+                    e.insert(value);
+                }
+            }
+        }
+
+        let mut root = Value::Map(root);
+        root.index_maps_into_seqs();
+        Ok(root)
+    }
+
+    /// Convert maps built by [`Value::from_flat_map`] with `0..n` keys into seqs.
+    fn index_maps_into_seqs(&mut self) {
+        if let Value::Map(m) = self {
+            m.values_mut().for_each(|v| v.index_maps_into_seqs());
+
+            let indexes: Option<Vec<usize>> = m
+                .keys()
+                .map(|k| match k {
+                    Value::Str(k) => parse_index(k),
+                    _ => None,
+                })
+                .collect();
+            let mut indexes = match indexes {
+                Some(indexes) if !indexes.is_empty() => indexes,
+                _ => return,
+            };
+            indexes.sort_unstable();
+            if indexes.iter().enumerate().any(|(idx, k)| idx != *k) {
+                return;
+            }
+
+            let mut entries: Vec<(usize, Value)> = std::mem::take(m)
+                .into_iter()
+                .map(|(k, v)| match k {
+                    Value::Str(k) => (parse_index(&k).expect("must be index"), v),
+                    _ => unreachable!("must be str key"),
+                })
+                .collect();
+            entries.sort_unstable_by_key(|(idx, _)| *idx);
+            *self = Value::Seq(entries.into_iter().map(|(_, v)| v).collect());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_flatten_to_map() {
+        let v = Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Some(Box::new(Value::Str("web".to_string()))),
+            Value::Str("empty".to_string()) => Value::Seq(vec![]),
+            Value::Str("log".to_string()) => Value::Map(indexmap! {
+                Value::U8(1) => Value::None,
+            }),
+        });
+
+        assert_eq!(
+            v.flatten_to_map("__"),
+            indexmap! {
+                "name".to_string() => Value::Str("web".to_string()),
+                "log__1".to_string() => Value::None,
+            }
+        );
+        assert_eq!(
+            Value::Bool(true).flatten_to_map("."),
+            indexmap! { "".to_string() => Value::Bool(true) }
+        );
+    }
+
+    #[test]
+    fn test_from_flat_map_round_trip() -> anyhow::Result<()> {
+        let v = Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Str("web".to_string()),
+            Value::Str("servers".to_string()) => Value::Seq(vec![
+                Value::Map(indexmap! {
+                    Value::Str("port".to_string()) => Value::U16(80),
+                    Value::Str("tls".to_string()) => Value::Bool(false),
+                }),
+                Value::Map(indexmap! {
+                    Value::Str("port".to_string()) => Value::U16(443),
+                    Value::Str("tls".to_string()) => Value::Bool(true),
+                }),
+            ]),
+            Value::Str("log".to_string()) => Value::Map(indexmap! {
+                Value::Str("level".to_string()) => Value::None,
+            }),
+        });
+
+        let flat = v.flatten_to_map(".");
+        assert_eq!(Value::from_flat_map(flat, ".")?, v);
+
+        let scalar = Value::I32(1);
+        assert_eq!(
+            Value::from_flat_map(scalar.flatten_to_map("."), ".")?,
+            scalar
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_flat_map_conflicts() {
+        let err = Value::from_flat_map(
+            indexmap! {
+                "log".to_string() => Value::Bool(true),
+                "log.level".to_string() => Value::Str("info".to_string()),
+            },
+            ".",
+        )
+        .expect_err("must fail");
+        assert_eq!(
+            err.to_string(),
+            "key `log.level` conflicts with value at `log`"
+        );
+
+        let err = Value::from_flat_map(
+            indexmap! {
+                "log.level".to_string() => Value::Str("info".to_string()),
+                "log".to_string() => Value::Bool(true),
+            },
+            ".",
+        )
+        .expect_err("must fail");
+        assert_eq!(err.to_string(), "key `log` conflicts with nested keys");
+
+        // Indexes with gaps stay maps.
+        let v = Value::from_flat_map(
+            indexmap! {
+                "a.0".to_string() => Value::Bool(true),
+                "a.2".to_string() => Value::Bool(false),
+            },
+            ".",
+        )
+        .expect("must succeed");
+        assert!(matches!(v.pointer("/a"), Some(Value::Map(_))));
+    }
+}
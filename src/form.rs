@@ -0,0 +1,204 @@
+use anyhow::anyhow;
+use indexmap::IndexMap;
+
+use crate::transform::key_to_string;
+use crate::{Error, Value};
+
+impl Value {
+    /// Encode a flat map or struct as `application/x-www-form-urlencoded`.
+    ///
+    /// - Entries are joined as `k=v&k2=v2` in their order, keys of maps use
+    ///   their string form.
+    /// - Bools, numbers, chars, strings and unit variants use their display form.
+    /// - `Some` and newtype wrappers are transparent, `None` entries are skipped.
+    /// - Nested seqs, maps, structs and other values return an error, bracket
+    ///   notation like `a[b]=c` is not supported.
+    ///
+    /// Chars other than ASCII letters, digits and `*-._` are percent-encoded,
+    /// spaces are encoded as `+`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::into_value;
+    /// # use anyhow::Result;
+    /// # use std::collections::BTreeMap;
+    /// # fn main() -> Result<()> {
+    /// let v = into_value(BTreeMap::from([("q", "a b&c"), ("page", "2")]))?;
+    /// assert_eq!(v.to_form_urlencoded()?, "page=2&q=a+b%26c");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_form_urlencoded(&self) -> Result<String, Error> {
+        let entries: Vec<(String, &Value)> = match self.inner() {
+            Value::Map(m) => m.iter().map(|(k, v)| (key_to_string(k), v)).collect(),
+            Value::Struct(_, fields) | Value::StructVariant { fields, .. } => {
+                fields.iter().map(|(k, v)| (k.to_string(), v)).collect()
+            }
+            v => {
+                return Err(Error(anyhow!(
+                    "invalid type: {:?}, expect map or struct",
+                    v
+                )))
+            }
+        };
+
+        let mut pairs = Vec::with_capacity(entries.len());
+        for (k, v) in entries {
+            let v = match v.inner() {
+                Value::None => continue,
+                v @ (Value::Bool(_)
+                | Value::I8(_)
+                | Value::I16(_)
+                | Value::I32(_)
+                | Value::I64(_)
+                | Value::I128(_)
+                | Value::U8(_)
+                | Value::U16(_)
+                | Value::U32(_)
+                | Value::U64(_)
+                | Value::U128(_)
+                | Value::F32(_)
+                | Value::F64(_)
+                | Value::Char(_)
+                | Value::Str(_)
+                | Value::UnitVariant { .. }) => key_to_string(v),
+                v => {
+                    return Err(Error(anyhow!(
+                        "can't encode {} of `{}` as form value",
+                        v.kind(),
+                        k
+                    )))
+                }
+            };
+            pairs.push(format!("{}={}", form_encode(&k), form_encode(&v)));
+        }
+        Ok(pairs.join("&"))
+    }
+
+    /// Decode `application/x-www-form-urlencoded` into a `Value::Map` of `Value::Str`.
+    ///
+    /// - Pairs are split by `&`, empty pairs are skipped. A pair without `=` has
+    ///   an empty value.
+    /// - `+` is decoded as space, `%XX` as the byte `XX`. Invalid escapes are
+    ///   kept as is and invalid UTF-8 is replaced by `U+FFFD`.
+    /// - The same key appearing twice: the later value wins.
+    ///
+    /// Deserialize the result with [`Config::lenient`] to parse values into numbers
+    /// and bools.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let v = Value::from_form_urlencoded("q=a+b%26c&page=2");
+    /// assert_eq!(v.pointer("/q"), Some(&Value::Str("a b&c".to_string())));
+    /// ```
+    ///
+    /// [`Config::lenient`]: crate::Config::lenient
+    pub fn from_form_urlencoded(input: &str) -> Value {
+        let mut m = IndexMap::new();
+        for pair in input.split('&').filter(|v| !v.is_empty()) {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            m.insert(Value::Str(form_decode(k)), Value::Str(form_decode(v)));
+        }
+        Value::Map(m)
+    }
+}
+
+fn form_encode(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for b in input.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'*' | b'-' | b'.' | b'_' => {
+                out.push(b as char)
+            }
+            b' ' => out.push('+'),
+            b => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+fn form_decode(input: &str) -> String {
+    let hex = |b: u8| (b as char).to_digit(16).map(|v| v as u8);
+
+    let bs = input.as_bytes();
+    let mut out = Vec::with_capacity(bs.len());
+    let mut idx = 0;
+    while idx < bs.len() {
+        match bs[idx] {
+            b'+' => out.push(b' '),
+            b'%' if idx + 2 < bs.len() => match (hex(bs[idx + 1]), hex(bs[idx + 2])) {
+                (Some(h), Some(l)) => {
+                    out.push(h << 4 | l);
+                    idx += 2;
+                }
+                _ => out.push(b'%'),
+            },
+            b => out.push(b),
+        }
+        idx += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::{from_value_with_config, into_value, Config};
+
+    #[test]
+    fn test_form_round_trip() -> anyhow::Result<()> {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Search {
+            q: String,
+            page: u32,
+            exact: bool,
+            lang: Option<String>,
+        }
+
+        let search = Search {
+            q: "100% rust & serde/中".to_string(),
+            page: 2,
+            exact: false,
+            lang: None,
+        };
+        let s = into_value(&search)?.to_form_urlencoded()?;
+        assert_eq!(s, "q=100%25+rust+%26+serde%2F%E4%B8%AD&page=2&exact=false");
+
+        let v = Value::from_form_urlencoded(&s);
+        let actual: Search = from_value_with_config(v, Config::new().lenient(true))?;
+        assert_eq!(actual, search);
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_form_urlencoded_nested() {
+        let v = Value::Struct(
+            "Search",
+            indexmap! { "tags" => Value::Seq(vec![Value::Str("a".to_string())]) },
+        );
+        let err = v.to_form_urlencoded().unwrap_err();
+        assert_eq!(err.to_string(), "can't encode seq of `tags` as form value");
+        assert!(Value::I32(1).to_form_urlencoded().is_err());
+    }
+
+    #[test]
+    fn test_from_form_urlencoded() {
+        let v = Value::from_form_urlencoded("a=1&&b&c=%zz%4&a=2&d=x%3Dy");
+        assert_eq!(
+            v,
+            Value::Map(indexmap! {
+                Value::Str("a".to_string()) => Value::Str("2".to_string()),
+                Value::Str("b".to_string()) => Value::Str("".to_string()),
+                Value::Str("c".to_string()) => Value::Str("%zz%4".to_string()),
+                Value::Str("d".to_string()) => Value::Str("x=y".to_string()),
+            })
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use std::io::{Read, Write};
+
+use anyhow::anyhow;
+use serde::de::DeserializeOwned;
+
+use crate::{from_value_with_config, Config, Error, Value};
+
+/// A data format that can be read into and written from [`Value`].
+///
+/// Implement this trait to plug a format into [`from_reader`] and [`Value::to_writer`].
+pub trait Format {
+    /// Read a complete document from `reader` into [`Value`].
+    fn read_value<R: Read>(&self, reader: R) -> Result<Value, Error>;
+
+    /// Write `value` as a complete document into `writer`.
+    ///
+    /// Formats are read-only by default, which returns an error here.
+    fn write_value<W: Write>(&self, writer: W, value: &Value) -> Result<(), Error> {
+        let _ = (writer, value);
+        Err(Error(anyhow!("format doesn't support writing")))
+    }
+
+    /// The [`Config`] [`from_reader`] deserializes values read from this format with.
+    ///
+    /// Default to [`Config::default`].
+    fn config(&self) -> Config {
+        Config::default()
+    }
+}
+
+/// JSON format backed by `serde_json`.
+///
+/// Trailing non-whitespace characters after the document are rejected.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json;
+
+#[cfg(feature = "json")]
+impl Format for Json {
+    fn read_value<R: Read>(&self, reader: R) -> Result<Value, Error> {
+        use serde::Deserialize;
+
+        let mut de = serde_json::Deserializer::from_reader(reader);
+        let v = Value::deserialize(&mut de).map_err(|e| Error(anyhow!("read json: {}", e)))?;
+        de.end().map_err(|e| Error(anyhow!("read json: {}", e)))?;
+        Ok(v)
+    }
+
+    fn write_value<W: Write>(&self, writer: W, value: &Value) -> Result<(), Error> {
+        serde_json::to_writer(writer, value).map_err(|e| Error(anyhow!("write json: {}", e)))
+    }
+
+    /// JSON has no options, so `Option` fields are read from `null` and plain values.
+    fn config(&self) -> Config {
+        Config::default().option_from_plain(true)
+    }
+}
+
+/// Read bytes of `format` from `reader` into `T: DeserializeOwned`.
+///
+/// The document is read into [`Value`] first and then converted like
+/// [`from_value_with_config`] with [`Format::config`].
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "json")]
+/// # fn main() -> anyhow::Result<()> {
+/// use serde_bridge::{from_reader, Json};
+///
+/// let v: Vec<u16> = from_reader(&b"[80, 443]"[..], Json)?;
+/// assert_eq!(v, vec![80, 443]);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "json"))]
+/// # fn main() {}
+/// ```
+pub fn from_reader<R: Read, T: DeserializeOwned>(
+    reader: R,
+    format: impl Format,
+) -> Result<T, Error> {
+    let config = format.config();
+    from_value_with_config(format.read_value(reader)?, config)
+}
+
+impl Value {
+    /// Write value into `writer` in `format`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "json")]
+    /// # fn main() -> anyhow::Result<()> {
+    /// use serde_bridge::{Json, Value};
+    ///
+    /// let mut buf = Vec::new();
+    /// Value::Seq(vec![Value::U16(80), Value::U16(443)]).to_writer(&mut buf, Json)?;
+    /// assert_eq!(buf, b"[80,443]");
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "json"))]
+    /// # fn main() {}
+    /// ```
+    pub fn to_writer<W: Write>(&self, writer: W, format: impl Format) -> Result<(), Error> {
+        format.write_value(writer, self)
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::fixtures::{test_struct, TestStruct, TEST_STRUCT_JSON};
+
+    #[test]
+    fn test_from_reader() -> anyhow::Result<()> {
+        let v: TestStruct = from_reader(Cursor::new(TEST_STRUCT_JSON), Json)?;
+        assert_eq!(v, test_struct());
+
+        let err = from_reader::<_, TestStruct>(Cursor::new("{} x"), Json).unwrap_err();
+        assert!(err.to_string().starts_with("read json: "), "{}", err);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_reader_option() -> anyhow::Result<()> {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct C {
+            name: String,
+            port: Option<u16>,
+        }
+
+        let v: C = from_reader(Cursor::new(r#"{"name": "web", "port": 80}"#), Json)?;
+        assert_eq!(
+            v,
+            C {
+                name: "web".to_string(),
+                port: Some(80)
+            }
+        );
+        let v: C = from_reader(Cursor::new(r#"{"name": "web", "port": null}"#), Json)?;
+        assert_eq!(
+            v,
+            C {
+                name: "web".to_string(),
+                port: None
+            }
+        );
+        Ok(())
+    }
+}
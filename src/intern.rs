@@ -0,0 +1,150 @@
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use indexmap::IndexMap;
+
+use crate::Value;
+
+/// Intern `name` so that it lives for the rest of the process.
+///
+/// Every distinct name is leaked once, interning the same name again returns
+/// the previous one.
+pub(crate) fn intern(name: &str) -> &'static str {
+    static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+    let mut names = NAMES.lock().unwrap_or_else(|e| e.into_inner());
+    match names.get(name) {
+        Some(name) => name,
+        None => {
+            let name: &'static str = Box::leak(name.to_string().into_boxed_str());
+            names.insert(name);
+            name
+        }
+    }
+}
+
+/// Constructors for values whose names are only known at runtime.
+///
+/// Names of structs, variants and fields are `&'static str` to match serde's
+/// data model, which keeps `Value` cheap to build from `Serialize` and lets
+/// users match on names directly. Switching them to `Cow<'static, str>` would
+/// break every pattern on `Value`, so names computed at runtime, for example
+/// from a YAML tag, are interned instead.
+///
+/// Interned names are never freed. This is fine for names from a schema or a
+/// fixed set of documents, but don't use these constructors for names from
+/// untrusted or unbounded input.
+impl Value {
+    /// Intern a name computed at runtime, see [`Value::new_struct`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let name = format!("{}{}", "Pre", "fix");
+    /// assert_eq!(Value::UnitStruct(Value::intern_name(&name)), Value::UnitStruct("Prefix"));
+    /// ```
+    pub fn intern_name(name: &str) -> &'static str {
+        intern(name)
+    }
+
+    /// Build `Value::UnitStruct` with a name computed at runtime.
+    pub fn new_unit_struct(name: &str) -> Value {
+        Value::UnitStruct(intern(name))
+    }
+
+    /// Build `Value::NewtypeStruct` with a name computed at runtime.
+    pub fn new_newtype_struct(name: &str, value: Value) -> Value {
+        Value::NewtypeStruct(intern(name), Box::new(value))
+    }
+
+    /// Build `Value::TupleStruct` with a name computed at runtime.
+    pub fn new_tuple_struct(name: &str, fields: Vec<Value>) -> Value {
+        Value::TupleStruct(intern(name), fields)
+    }
+
+    /// Build `Value::Struct` with struct and field names computed at runtime.
+    ///
+    /// Names are interned for the rest of the process: every distinct name is
+    /// leaked once. Don't use this for names from unbounded input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let tag = "!Server".trim_start_matches('!');
+    /// let v = Value::new_struct(tag, vec![("port", Value::U16(80))]);
+    /// assert!(matches!(v, Value::Struct("Server", _)));
+    /// ```
+    pub fn new_struct<K: AsRef<str>>(
+        name: &str,
+        fields: impl IntoIterator<Item = (K, Value)>,
+    ) -> Value {
+        let fields: IndexMap<&'static str, Value> = fields
+            .into_iter()
+            .map(|(k, v)| (intern(k.as_ref()), v))
+            .collect();
+        Value::Struct(intern(name), fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let a = intern(&["a", "bc"].concat());
+        let b = intern(&format!("ab{}", "c"));
+        assert!(std::ptr::eq(a, b));
+    }
+
+    #[test]
+    fn test_new_struct() -> anyhow::Result<()> {
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Server {
+            host: String,
+            port: u16,
+        }
+
+        let name: String = ["Ser", "ver"].concat();
+        let fields = vec![
+            ("host".to_string(), Value::Str("localhost".to_string())),
+            (format!("p{}", "ort"), Value::U16(80)),
+        ];
+        let v = Value::new_struct(&name, fields);
+        assert_eq!(
+            v,
+            Value::Struct(
+                "Server",
+                indexmap! {
+                    "host" => Value::Str("localhost".to_string()),
+                    "port" => Value::U16(80),
+                }
+            )
+        );
+        assert_eq!(
+            crate::from_value::<Server>(v)?,
+            Server {
+                host: "localhost".to_string(),
+                port: 80
+            }
+        );
+
+        assert_eq!(
+            Value::new_newtype_struct(&name, Value::Unit),
+            Value::NewtypeStruct("Server", Box::new(Value::Unit))
+        );
+        assert_eq!(
+            Value::new_tuple_struct(&name, vec![]),
+            Value::TupleStruct("Server", vec![])
+        );
+        assert_eq!(Value::new_unit_struct(&name), Value::UnitStruct("Server"));
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use std::fmt::{Display, Formatter};
+
+use crate::Value;
+
+/// The variant of a [`Value`] without its content.
+///
+/// # Examples
+///
+/// ```
+/// use serde_bridge::{Value, ValueKind};
+///
+/// assert_eq!(Value::I64(1).kind(), ValueKind::I64);
+/// assert_eq!(ValueKind::Str.to_string(), "str");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueKind {
+    Bool,
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    F32,
+    F64,
+    Char,
+    Str,
+    Bytes,
+    None,
+    Some,
+    Unit,
+    UnitStruct,
+    UnitVariant,
+    NewtypeStruct,
+    NewtypeVariant,
+    Seq,
+    Tuple,
+    TupleStruct,
+    TupleVariant,
+    Map,
+    Struct,
+    StructVariant,
+    Shared,
+}
+
+impl ValueKind {
+    /// Returns `true` for integer kinds from `I8` to `U128`.
+    pub fn is_integer(self) -> bool {
+        matches!(
+            self,
+            ValueKind::I8
+                | ValueKind::I16
+                | ValueKind::I32
+                | ValueKind::I64
+                | ValueKind::I128
+                | ValueKind::U8
+                | ValueKind::U16
+                | ValueKind::U32
+                | ValueKind::U64
+                | ValueKind::U128
+        )
+    }
+
+    /// Returns `true` for `F32` and `F64`.
+    pub fn is_float(self) -> bool {
+        matches!(self, ValueKind::F32 | ValueKind::F64)
+    }
+}
+
+impl Display for ValueKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            ValueKind::Bool => "bool",
+            ValueKind::I8 => "i8",
+            ValueKind::I16 => "i16",
+            ValueKind::I32 => "i32",
+            ValueKind::I64 => "i64",
+            ValueKind::I128 => "i128",
+            ValueKind::U8 => "u8",
+            ValueKind::U16 => "u16",
+            ValueKind::U32 => "u32",
+            ValueKind::U64 => "u64",
+            ValueKind::U128 => "u128",
+            ValueKind::F32 => "f32",
+            ValueKind::F64 => "f64",
+            ValueKind::Char => "char",
+            ValueKind::Str => "str",
+            ValueKind::Bytes => "bytes",
+            ValueKind::None => "none",
+            ValueKind::Some => "some",
+            ValueKind::Unit => "unit",
+            ValueKind::UnitStruct => "unit struct",
+            ValueKind::UnitVariant => "unit variant",
+            ValueKind::NewtypeStruct => "newtype struct",
+            ValueKind::NewtypeVariant => "newtype variant",
+            ValueKind::Seq => "seq",
+            ValueKind::Tuple => "tuple",
+            ValueKind::TupleStruct => "tuple struct",
+            ValueKind::TupleVariant => "tuple variant",
+            ValueKind::Map => "map",
+            ValueKind::Struct => "struct",
+            ValueKind::StructVariant => "struct variant",
+            ValueKind::Shared => "shared",
+        };
+        f.write_str(s)
+    }
+}
+
+impl Value {
+    /// Return the [`ValueKind`] of this value.
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Value::Bool(_) => ValueKind::Bool,
+            Value::I8(_) => ValueKind::I8,
+            Value::I16(_) => ValueKind::I16,
+            Value::I32(_) => ValueKind::I32,
+            Value::I64(_) => ValueKind::I64,
+            Value::I128(_) => ValueKind::I128,
+            Value::U8(_) => ValueKind::U8,
+            Value::U16(_) => ValueKind::U16,
+            Value::U32(_) => ValueKind::U32,
+            Value::U64(_) => ValueKind::U64,
+            Value::U128(_) => ValueKind::U128,
+            Value::F32(_) => ValueKind::F32,
+            Value::F64(_) => ValueKind::F64,
+            Value::Char(_) => ValueKind::Char,
+            Value::Str(_) => ValueKind::Str,
+            Value::Bytes(_) => ValueKind::Bytes,
+            Value::None => ValueKind::None,
+            Value::Some(_) => ValueKind::Some,
+            Value::Unit => ValueKind::Unit,
+            Value::UnitStruct(_) => ValueKind::UnitStruct,
+            Value::UnitVariant { .. } => ValueKind::UnitVariant,
+            Value::NewtypeStruct(_, _) => ValueKind::NewtypeStruct,
+            Value::NewtypeVariant { .. } => ValueKind::NewtypeVariant,
+            Value::Seq(_) => ValueKind::Seq,
+            Value::Tuple(_) => ValueKind::Tuple,
+            Value::TupleStruct(_, _) => ValueKind::TupleStruct,
+            Value::TupleVariant { .. } => ValueKind::TupleVariant,
+            Value::Map(_) => ValueKind::Map,
+            Value::Struct(_, _) => ValueKind::Struct,
+            Value::StructVariant { .. } => ValueKind::StructVariant,
+            Value::Shared(_) => ValueKind::Shared,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind() {
+        assert_eq!(Value::Str("a".to_string()).kind(), ValueKind::Str);
+        assert_eq!(Value::Some(Box::new(Value::Unit)).kind(), ValueKind::Some);
+        assert!(Value::U128(1).kind().is_integer());
+        assert!(!Value::F32(1.0).kind().is_integer());
+        assert!(Value::F32(1.0).kind().is_float());
+        assert_eq!(ValueKind::TupleStruct.to_string(), "tuple struct");
+    }
+}
@@ -26,11 +26,95 @@
 mod value;
 pub use value::Value;
 
+mod macros;
+#[doc(hidden)]
+pub use macros::__private;
+
 mod de;
-pub use de::{from_value, FromValue};
+pub use de::{
+    from_value, from_value_collect_errors, from_value_in_place, from_value_tracked,
+    from_value_with_config, Deserializer, FromValue,
+};
 
 mod ser;
-pub use ser::{into_value, IntoValue};
+pub use ser::{into_value, ConfiguredValue, IntoValue, Serializer};
+
+mod path;
+
+mod access;
+
+mod intern;
+
+mod kind;
+pub use kind::ValueKind;
+
+mod schema;
+pub use schema::{Schema, SchemaError};
+
+mod walk;
+
+mod number;
+
+mod transform;
+pub use transform::BytePolicy;
+
+mod shape;
+
+mod cmp;
+
+mod validate;
+
+mod env;
+
+#[cfg(feature = "form")]
+mod form;
+
+mod flat;
+
+mod merge;
+
+mod patch;
+pub use patch::PatchOp;
+
+mod pretty;
+pub use pretty::BytesFormat;
+
+#[cfg(feature = "base64")]
+mod base64;
+
+#[cfg(feature = "chrono")]
+mod timestamp;
+#[cfg(feature = "chrono")]
+pub use timestamp::TimestampFormat;
+
+mod coerce;
+
+mod convert;
+pub use convert::{convert, convert_with_config};
+
+#[cfg(feature = "test-util")]
+mod testing;
+#[cfg(feature = "test-util")]
+pub use testing::assert_roundtrip;
+
+#[cfg(feature = "binary")]
+mod binary;
+
+mod format;
+#[cfg(feature = "json")]
+pub use format::Json;
+pub use format::{from_reader, Format};
+
+mod transcode;
+pub use transcode::transcode;
+
+mod config;
+pub use config::{Config, NanHandling};
 
 mod error;
-use error::Error;
+pub use error::Error;
+
+// Not every fixture is used with every feature set.
+#[cfg(test)]
+#[allow(dead_code)]
+mod fixtures;
@@ -0,0 +1,318 @@
+/// Construct a [`Value`](crate::Value) from a JSON-like literal.
+///
+/// - `null` becomes `Value::None`.
+/// - `[...]` becomes `Value::Seq`.
+/// - `{ key: value, ... }` becomes `Value::Map`, keys are built the same way as values.
+/// - Bare integer literals without suffix, like `1`, become `Value::I64` and bare
+///   float literals without suffix become `Value::F64`, string literals become
+///   `Value::Str`.
+/// - Any other expression is converted via [`into_value`](crate::into_value) and
+///   keeps its Rust type, so `(1u8)` becomes `Value::U8`.
+///
+/// The `I64`/`F64` default only applies to bare literals. Arithmetic like `1 + 2`
+/// is an expression that Rust infers as `i32`, so it becomes `Value::I32`. Add a
+/// suffix like `1i64 + 2` to pick the width. Literals with suffix, like `1u8`, must
+/// be wrapped in parentheses.
+///
+/// # Panics
+///
+/// Panics if an expression fails to serialize into [`Value`](crate::Value).
+///
+/// # Examples
+///
+/// ```
+/// use indexmap::indexmap;
+/// use serde_bridge::{value, Value};
+///
+/// let port = 8080u16;
+/// let v = value!({
+///     "name": "web",
+///     "port": port,
+///     "tags": [1, 2.5, null],
+/// });
+/// assert_eq!(
+///     v,
+///     Value::Map(indexmap! {
+///         Value::Str("name".to_string()) => Value::Str("web".to_string()),
+///         Value::Str("port".to_string()) => Value::U16(8080),
+///         Value::Str("tags".to_string()) => Value::Seq(vec![
+///             Value::I64(1),
+///             Value::F64(2.5),
+///             Value::None,
+///         ]),
+///     })
+/// );
+/// ```
+#[macro_export]
+macro_rules! value {
+    ($($value:tt)+) => {
+        $crate::value_internal!($($value)+)
+    };
+}
+
+/// Implementation of [`value!`], adapted from the tt-muncher of `serde_json::json!`.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! value_internal {
+    // Done with trailing comma.
+    (@seq [$($elems:expr,)*]) => {
+        vec![$($elems,)*]
+    };
+
+    // Done without trailing comma.
+    (@seq [$($elems:expr),*]) => {
+        vec![$($elems),*]
+    };
+
+    // Next element is `null`.
+    (@seq [$($elems:expr,)*] null $($rest:tt)*) => {
+        $crate::value_internal!(@seq [$($elems,)* $crate::value_internal!(null)] $($rest)*)
+    };
+
+    // Next element is a seq.
+    (@seq [$($elems:expr,)*] [$($seq:tt)*] $($rest:tt)*) => {
+        $crate::value_internal!(@seq [$($elems,)* $crate::value_internal!([$($seq)*])] $($rest)*)
+    };
+
+    // Next element is a map.
+    (@seq [$($elems:expr,)*] {$($map:tt)*} $($rest:tt)*) => {
+        $crate::value_internal!(@seq [$($elems,)* $crate::value_internal!({$($map)*})] $($rest)*)
+    };
+
+    // Next element is a literal followed by comma.
+    (@seq [$($elems:expr,)*] $next:literal, $($rest:tt)*) => {
+        $crate::value_internal!(@seq [$($elems,)* $crate::value_internal!($next),] $($rest)*)
+    };
+
+    // Last element is a literal with no trailing comma.
+    (@seq [$($elems:expr,)*] $last:literal) => {
+        $crate::value_internal!(@seq [$($elems,)* $crate::value_internal!($last)])
+    };
+
+    // Next element is an expression followed by comma.
+    (@seq [$($elems:expr,)*] $next:expr, $($rest:tt)*) => {
+        $crate::value_internal!(@seq [$($elems,)* $crate::value_internal!($next),] $($rest)*)
+    };
+
+    // Last element is an expression with no trailing comma.
+    (@seq [$($elems:expr,)*] $last:expr) => {
+        $crate::value_internal!(@seq [$($elems,)* $crate::value_internal!($last)])
+    };
+
+    // Comma after the most recent element.
+    (@seq [$($elems:expr),*] , $($rest:tt)*) => {
+        $crate::value_internal!(@seq [$($elems,)*] $($rest)*)
+    };
+
+    // Done.
+    (@map $map:ident () () ()) => {};
+
+    // Insert the current entry followed by trailing comma.
+    (@map $map:ident [$($key:tt)+] ($value:expr) , $($rest:tt)*) => {
+        let _ = $map.insert($crate::value_internal!($($key)+), $value);
+        $crate::value_internal!(@map $map () ($($rest)*) ($($rest)*));
+    };
+
+    // Insert the last entry without trailing comma.
+    (@map $map:ident [$($key:tt)+] ($value:expr)) => {
+        let _ = $map.insert($crate::value_internal!($($key)+), $value);
+    };
+
+    // Next value is `null`.
+    (@map $map:ident ($($key:tt)+) (: null $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@map $map [$($key)+] ($crate::value_internal!(null)) $($rest)*);
+    };
+
+    // Next value is a seq.
+    (@map $map:ident ($($key:tt)+) (: [$($seq:tt)*] $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@map $map [$($key)+] ($crate::value_internal!([$($seq)*])) $($rest)*);
+    };
+
+    // Next value is a map.
+    (@map $map:ident ($($key:tt)+) (: {$($inner:tt)*} $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@map $map [$($key)+] ($crate::value_internal!({$($inner)*})) $($rest)*);
+    };
+
+    // Next value is a literal followed by comma.
+    (@map $map:ident ($($key:tt)+) (: $value:literal , $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@map $map [$($key)+] ($crate::value_internal!($value)) , $($rest)*);
+    };
+
+    // Last value is a literal with no trailing comma.
+    (@map $map:ident ($($key:tt)+) (: $value:literal) $copy:tt) => {
+        $crate::value_internal!(@map $map [$($key)+] ($crate::value_internal!($value)));
+    };
+
+    // Next value is an expression followed by comma.
+    (@map $map:ident ($($key:tt)+) (: $value:expr , $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@map $map [$($key)+] ($crate::value_internal!($value)) , $($rest)*);
+    };
+
+    // Last value is an expression with no trailing comma.
+    (@map $map:ident ($($key:tt)+) (: $value:expr) $copy:tt) => {
+        $crate::value_internal!(@map $map [$($key)+] ($crate::value_internal!($value)));
+    };
+
+    // Missing value for last entry, trigger a reasonable error message.
+    (@map $map:ident ($($key:tt)+) (:) $copy:tt) => {
+        $crate::value_internal!();
+    };
+
+    // Missing colon and value for last entry, trigger a reasonable error message.
+    (@map $map:ident ($($key:tt)+) () $copy:tt) => {
+        $crate::value_internal!();
+    };
+
+    // Munch a token into the current key.
+    (@map $map:ident ($($key:tt)*) ($tt:tt $($rest:tt)*) $copy:tt) => {
+        $crate::value_internal!(@map $map ($($key)* $tt) ($($rest)*) ($($rest)*));
+    };
+
+    (null) => {
+        $crate::Value::None
+    };
+
+    ([]) => {
+        $crate::Value::Seq(vec![])
+    };
+
+    ([ $($tt:tt)+ ]) => {
+        $crate::Value::Seq($crate::value_internal!(@seq [] $($tt)+))
+    };
+
+    ({}) => {
+        $crate::Value::Map($crate::__private::IndexMap::new())
+    };
+
+    ({ $($tt:tt)+ }) => {
+        $crate::Value::Map({
+            let mut map = $crate::__private::IndexMap::new();
+            $crate::value_internal!(@map map () ($($tt)+) ($($tt)+));
+            map
+        })
+    };
+
+    ($value:literal) => {
+        $crate::__private::literal($value)
+    };
+
+    ($other:expr) => {
+        $crate::into_value(&$other).expect("serialize into value must success")
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use indexmap::IndexMap;
+
+    use crate::Value;
+
+    /// Literals supported by [`value!`], their types are picked by the only impl
+    /// matching an unsuffixed literal: `i64` for integers and `f64` for floats.
+    pub trait Literal {
+        fn into_literal_value(self) -> Value;
+    }
+
+    impl Literal for bool {
+        fn into_literal_value(self) -> Value {
+            Value::Bool(self)
+        }
+    }
+
+    impl Literal for i64 {
+        fn into_literal_value(self) -> Value {
+            Value::I64(self)
+        }
+    }
+
+    impl Literal for f64 {
+        fn into_literal_value(self) -> Value {
+            Value::F64(self)
+        }
+    }
+
+    impl Literal for char {
+        fn into_literal_value(self) -> Value {
+            Value::Char(self)
+        }
+    }
+
+    impl Literal for &str {
+        fn into_literal_value(self) -> Value {
+            Value::Str(self.to_string())
+        }
+    }
+
+    pub fn literal(v: impl Literal) -> Value {
+        v.into_literal_value()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use crate::Value;
+
+    #[test]
+    fn test_value_macro_scalars() {
+        assert_eq!(value!(null), Value::None);
+        assert_eq!(value!(true), Value::Bool(true));
+        assert_eq!(value!(1), Value::I64(1));
+        assert_eq!(value!(-1), Value::I64(-1));
+        assert_eq!(value!(1.5), Value::F64(1.5));
+        assert_eq!(value!('a'), Value::Char('a'));
+        assert_eq!(value!("a"), Value::Str("a".to_string()));
+        assert_eq!(value!((1u8)), Value::U8(1));
+
+        let s = "b".to_string();
+        assert_eq!(value!(s), Value::Str("b".to_string()));
+        assert_eq!(value!(Some(1u32)), Value::Some(Box::new(Value::U32(1))));
+    }
+
+    #[test]
+    fn test_value_macro_containers() {
+        assert_eq!(value!([]), Value::Seq(vec![]));
+        assert_eq!(value!({}), Value::Map(indexmap! {}));
+
+        let port = 8080u16;
+        let v = value!({
+            "a": true,
+            "b": [1, 2, 3],
+            "c": { "d": null, "e": [], },
+            "port": port,
+            (1u8 + 1): "two",
+            "sum": 1i64 + 2,
+        });
+        assert_eq!(
+            v,
+            Value::Map(indexmap! {
+                Value::Str("a".to_string()) => Value::Bool(true),
+                Value::Str("b".to_string()) => Value::Seq(vec![
+                    Value::I64(1),
+                    Value::I64(2),
+                    Value::I64(3),
+                ]),
+                Value::Str("c".to_string()) => Value::Map(indexmap! {
+                    Value::Str("d".to_string()) => Value::None,
+                    Value::Str("e".to_string()) => Value::Seq(vec![]),
+                }),
+                Value::Str("port".to_string()) => Value::U16(8080),
+                Value::U8(2) => Value::Str("two".to_string()),
+                Value::Str("sum".to_string()) => Value::I64(3),
+            })
+        );
+
+        let v = value!([null, [true], { "k": 'v' }, port, -0.5]);
+        assert_eq!(
+            v,
+            Value::Seq(vec![
+                Value::None,
+                Value::Seq(vec![Value::Bool(true)]),
+                Value::Map(indexmap! { Value::Str("k".to_string()) => Value::Char('v') }),
+                Value::U16(8080),
+                Value::F64(-0.5),
+            ])
+        );
+    }
+}
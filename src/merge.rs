@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use indexmap::map::Entry;
+
+use crate::Value;
+
+impl Value {
+    /// Fill keys absent from `self` with the ones from `defaults`.
+    ///
+    /// Values in `self` always win, `defaults` only fill the gaps:
+    ///
+    /// - When both sides are objects (`Map` or `Struct`), keys missing from `self`
+    ///   are inserted from `defaults`, and keys present on both sides are merged
+    ///   recursively. Struct fields are inserted into maps as `Value::Str` keys.
+    /// - Otherwise `self` is kept as is. Scalars and seqs are never merged
+    ///   element-wise, so a seq in `defaults` only fills a key that is absent.
+    /// - `Value::None` counts as present, an explicit `null` is not replaced.
+    /// - `Some` and newtype wrappers are looked through on both sides.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::indexmap;
+    /// use serde_bridge::Value;
+    ///
+    /// let mut v = Value::Map(indexmap! { Value::Str("port".to_string()) => Value::U16(8080) });
+    /// v.merge_defaults(Value::Map(indexmap! {
+    ///     Value::Str("port".to_string()) => Value::U16(80),
+    ///     Value::Str("host".to_string()) => Value::Str("localhost".to_string()),
+    /// }));
+    /// assert_eq!(v.pointer("/port"), Some(&Value::U16(8080)));
+    /// assert_eq!(v.pointer("/host"), Some(&Value::Str("localhost".to_string())));
+    /// ```
+    pub fn merge_defaults(&mut self, defaults: Value) {
+        match (self.inner_mut(), defaults.into_inner()) {
+            (Value::Map(m), Value::Map(defaults)) => {
+                for (k, v) in defaults {
+                    merge_entry(m.entry(k), v)
+                }
+            }
+            (Value::Map(m), Value::Struct(_, defaults)) => {
+                for (k, v) in defaults {
+                    merge_entry(m.entry(Value::Str(k.to_string())), v)
+                }
+            }
+            (Value::Struct(_, fields), Value::Struct(_, defaults)) => {
+                for (k, v) in defaults {
+                    merge_entry(fields.entry(k), v)
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Unwrap transparent wrappers like `Some` and `NewtypeStruct` by value.
+    fn into_inner(self) -> Value {
+        match self {
+            Value::Some(v) | Value::NewtypeStruct(_, v) => v.into_inner(),
+            Value::NewtypeVariant { value, .. } => value.into_inner(),
+            Value::Shared(v) => Arc::try_unwrap(v)
+                .unwrap_or_else(|v| (*v).clone())
+                .into_inner(),
+            v => v,
+        }
+    }
+}
+
+fn merge_entry<K>(entry: Entry<'_, K, Value>, default: Value) {
+    match entry {
+        Entry::Occupied(mut e) => e.get_mut().merge_defaults(default),
+        Entry::Vacant(e) => {
+            e.insert(default);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_merge_defaults() {
+        let mut user = Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Str("web".to_string()),
+            Value::Str("tags".to_string()) => Value::Seq(vec![Value::Str("prod".to_string())]),
+            Value::Str("log".to_string()) => Value::Some(Box::new(Value::Map(indexmap! {
+                Value::Str("level".to_string()) => Value::Str("debug".to_string()),
+            }))),
+            Value::Str("tls".to_string()) => Value::None,
+        });
+        let defaults = Value::Struct(
+            "Config",
+            indexmap! {
+                "name" => Value::Str("app".to_string()),
+                "port" => Value::U16(80),
+                "tags" => Value::Seq(vec![Value::Str("a".to_string()), Value::Str("b".to_string())]),
+                "log" => Value::Struct("Log", indexmap! {
+                    "level" => Value::Str("info".to_string()),
+                    "file" => Value::Str("app.log".to_string()),
+                }),
+                "tls" => Value::Bool(true),
+            },
+        );
+        user.merge_defaults(defaults);
+
+        assert_eq!(
+            user,
+            Value::Map(indexmap! {
+                Value::Str("name".to_string()) => Value::Str("web".to_string()),
+                Value::Str("tags".to_string()) => Value::Seq(vec![Value::Str("prod".to_string())]),
+                Value::Str("log".to_string()) => Value::Some(Box::new(Value::Map(indexmap! {
+                    Value::Str("level".to_string()) => Value::Str("debug".to_string()),
+                    Value::Str("file".to_string()) => Value::Str("app.log".to_string()),
+                }))),
+                Value::Str("tls".to_string()) => Value::None,
+                Value::Str("port".to_string()) => Value::U16(80),
+            })
+        );
+    }
+}
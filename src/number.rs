@@ -0,0 +1,302 @@
+use crate::Value;
+
+impl Value {
+    /// Build a number [`Value`] from `f64`.
+    ///
+    /// Selection rules:
+    ///
+    /// - If `integral_as_int` is `true` and `f` is finite without fractional part:
+    ///   - `I64` if `f` is in `[i64::MIN, i64::MAX]`.
+    ///   - `U64` if `f` is in `(i64::MAX, u64::MAX]`.
+    /// - Otherwise `F64`, including `NaN`, infinities and out of range values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// assert_eq!(Value::number_from_f64(4.0, true), Value::I64(4));
+    /// assert_eq!(Value::number_from_f64(4.0, false), Value::F64(4.0));
+    /// assert_eq!(Value::number_from_f64(4.5, true), Value::F64(4.5));
+    /// ```
+    pub fn number_from_f64(f: f64, integral_as_int: bool) -> Value {
+        // 2^63 and 2^64 are exactly representable in f64.
+        const I64_BOUND: f64 = 9_223_372_036_854_775_808.0;
+        const U64_BOUND: f64 = 18_446_744_073_709_551_616.0;
+
+        if !integral_as_int || !f.is_finite() || f.fract() != 0.0 {
+            return Value::F64(f);
+        }
+
+        if (-I64_BOUND..I64_BOUND).contains(&f) {
+            Value::I64(f as i64)
+        } else if (0.0..U64_BOUND).contains(&f) {
+            Value::U64(f as u64)
+        } else {
+            Value::F64(f)
+        }
+    }
+
+    /// Build a number [`Value`] from `i128`.
+    ///
+    /// Selection rules:
+    ///
+    /// - `I64` if `i` fits in `i64`.
+    /// - `U64` if `i` fits in `u64`.
+    /// - `I128` otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// assert_eq!(Value::number_from_i128(-1), Value::I64(-1));
+    /// assert_eq!(Value::number_from_i128(u64::MAX as i128), Value::U64(u64::MAX));
+    /// assert_eq!(Value::number_from_i128(i128::MAX), Value::I128(i128::MAX));
+    /// ```
+    pub fn number_from_i128(i: i128) -> Value {
+        if let Ok(v) = i64::try_from(i) {
+            Value::I64(v)
+        } else if let Ok(v) = u64::try_from(i) {
+            Value::U64(v)
+        } else {
+            Value::I128(i)
+        }
+    }
+
+    /// Return the value of any integer, or integral float, if it fits in `i128`.
+    ///
+    /// Returns `None` for non-numeric values, floats with fractional part,
+    /// non-finite floats and values out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// assert_eq!(Value::U64(u64::MAX).to_i128(), Some(u64::MAX as i128));
+    /// assert_eq!(Value::F64(-2.0).to_i128(), Some(-2));
+    /// assert_eq!(Value::F64(2.5).to_i128(), None);
+    /// ```
+    pub fn to_i128(&self) -> Option<i128> {
+        // 2^127 is exactly representable in f64.
+        const I128_BOUND: f64 = 170_141_183_460_469_231_731_687_303_715_884_105_728.0;
+
+        match self.unshared() {
+            Value::I8(v) => Some(*v as i128),
+            Value::I16(v) => Some(*v as i128),
+            Value::I32(v) => Some(*v as i128),
+            Value::I64(v) => Some(*v as i128),
+            Value::I128(v) => Some(*v),
+            Value::U8(v) => Some(*v as i128),
+            Value::U16(v) => Some(*v as i128),
+            Value::U32(v) => Some(*v as i128),
+            Value::U64(v) => Some(*v as i128),
+            Value::U128(v) => i128::try_from(*v).ok(),
+            Value::F32(_) | Value::F64(_) => {
+                let f = self.to_f64_lossy()?;
+                (f.fract() == 0.0 && (-I128_BOUND..I128_BOUND).contains(&f)).then_some(f as i128)
+            }
+            _ => None,
+        }
+    }
+
+    /// Return the value of any integer, or integral float, if it fits in `u128`.
+    ///
+    /// Returns `None` for non-numeric values, floats with fractional part,
+    /// non-finite floats and values out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// assert_eq!(Value::I8(1).to_u128(), Some(1));
+    /// assert_eq!(Value::I8(-1).to_u128(), None);
+    /// ```
+    pub fn to_u128(&self) -> Option<u128> {
+        // 2^128 is exactly representable in f64.
+        const U128_BOUND: f64 = 340_282_366_920_938_463_463_374_607_431_768_211_456.0;
+
+        match self.unshared() {
+            Value::U128(v) => Some(*v),
+            Value::F32(_) | Value::F64(_) => {
+                let f = self.to_f64_lossy()?;
+                (f.fract() == 0.0 && (0.0..U128_BOUND).contains(&f)).then_some(f as u128)
+            }
+            v => u128::try_from(v.to_i128()?).ok(),
+        }
+    }
+
+    /// Return any number as `f64`, rounding integers that can't be represented exactly.
+    ///
+    /// Returns `None` for non-numeric values. This is the same as [`Value::as_f64`],
+    /// named to pair with [`Value::to_i128`] and [`Value::to_u128`].
+    pub fn to_f64_lossy(&self) -> Option<f64> {
+        self.as_f64()
+    }
+
+    /// Shrink every number in the tree into the smallest variant holding it exactly.
+    ///
+    /// Self-describing formats like JSON only produce `I64`, `U64` and `F64`. When
+    /// data is known to be bounded, tightening makes values read from them equal to
+    /// values serialized from narrower Rust types.
+    ///
+    /// Rules:
+    ///
+    /// - Non-negative integers become the smallest of `U8`, `U16`, `U32`, `U64` and `U128`.
+    /// - Negative integers become the smallest of `I8`, `I16`, `I32`, `I64` and `I128`.
+    /// - `F64` becomes `F32` if it survives the round trip through `f32`.
+    ///
+    /// Floats never become integers. Map keys are not changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let mut v = Value::Seq(vec![Value::I64(1), Value::I64(-300), Value::F64(0.5)]);
+    /// v.tighten();
+    /// assert_eq!(v, Value::Seq(vec![Value::U8(1), Value::I16(-300), Value::F32(0.5)]));
+    /// ```
+    pub fn tighten(&mut self) {
+        self.visit_mut(&mut |v| {
+            if let Some(t) = v.tightened() {
+                *v = t
+            }
+        });
+    }
+
+    fn tightened(&self) -> Option<Value> {
+        if let Value::F64(f) = self {
+            return f64_to_f32_exact(*f).map(Value::F32);
+        }
+        if !self.kind().is_integer() {
+            return None;
+        }
+
+        let v = if let Some(u) = self.to_u128() {
+            if let Ok(v) = u8::try_from(u) {
+                Value::U8(v)
+            } else if let Ok(v) = u16::try_from(u) {
+                Value::U16(v)
+            } else if let Ok(v) = u32::try_from(u) {
+                Value::U32(v)
+            } else if let Ok(v) = u64::try_from(u) {
+                Value::U64(v)
+            } else {
+                Value::U128(u)
+            }
+        } else {
+            let i = self.to_i128().expect("negative integer must fit in i128");
+            if let Ok(v) = i8::try_from(i) {
+                Value::I8(v)
+            } else if let Ok(v) = i16::try_from(i) {
+                Value::I16(v)
+            } else if let Ok(v) = i32::try_from(i) {
+                Value::I32(v)
+            } else if let Ok(v) = i64::try_from(i) {
+                Value::I64(v)
+            } else {
+                Value::I128(i)
+            }
+        };
+        Some(v)
+    }
+}
+
+/// Narrow `f64` into `f32` if no precision is lost, `NaN` is always kept.
+pub(crate) fn f64_to_f32_exact(f: f64) -> Option<f32> {
+    let n = f as f32;
+    (n as f64 == f || f.is_nan()).then_some(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_number_from_f64() {
+        assert_eq!(Value::number_from_f64(-4.0, true), Value::I64(-4));
+        assert_eq!(Value::number_from_f64(-4.0, false), Value::F64(-4.0));
+        assert_eq!(Value::number_from_f64(3.25, true), Value::F64(3.25));
+        assert_eq!(
+            Value::number_from_f64(1e19, true),
+            Value::U64(10_000_000_000_000_000_000)
+        );
+        assert_eq!(Value::number_from_f64(1e20, true), Value::F64(1e20));
+        assert!(matches!(Value::number_from_f64(f64::NAN, true), Value::F64(v) if v.is_nan()));
+    }
+
+    #[test]
+    fn test_to_i128_and_u128() {
+        assert_eq!(Value::U64(u64::MAX).to_i128(), Some(u64::MAX as i128));
+        assert_eq!(Value::U128(u128::MAX).to_i128(), None);
+        assert_eq!(Value::U128(u128::MAX).to_u128(), Some(u128::MAX));
+        assert_eq!(Value::I64(-1).to_u128(), None);
+        assert_eq!(Value::F32(3.0).to_u128(), Some(3));
+        assert_eq!(Value::F64(-1.0).to_u128(), None);
+        assert_eq!(Value::F64(1e40).to_i128(), None);
+        assert_eq!(Value::F64(f64::NAN).to_i128(), None);
+        assert_eq!(Value::F64(f64::INFINITY).to_u128(), None);
+        assert_eq!(Value::Str("1".to_string()).to_i128(), None);
+        assert_eq!(Value::I64(-1).shared().to_i128(), Some(-1));
+        assert_eq!(Value::F64(2.0).shared().to_i128(), Some(2));
+        assert_eq!(Value::U128(u128::MAX).shared().to_u128(), Some(u128::MAX));
+        assert_eq!(Value::U8(3).shared().to_f64_lossy(), Some(3.0));
+
+        // Out of range for u64 but not for u128.
+        let v = Value::U128(u64::MAX as u128 + 1);
+        assert_eq!(v.as_u64(), None);
+        assert_eq!(v.to_u128(), Some(u64::MAX as u128 + 1));
+        assert_eq!(v.to_f64_lossy(), Some(18_446_744_073_709_551_616.0));
+    }
+
+    #[test]
+    fn test_number_from_i128() {
+        assert_eq!(Value::number_from_i128(42), Value::I64(42));
+        assert_eq!(
+            Value::number_from_i128(i64::MAX as i128 + 1),
+            Value::U64(i64::MAX as u64 + 1)
+        );
+        assert_eq!(Value::number_from_i128(i128::MIN), Value::I128(i128::MIN));
+    }
+
+    #[test]
+    fn test_tighten() {
+        let mut v = Value::Map(indexmap! {
+            Value::I64(1000) => Value::I64(0),
+            Value::Str("a".to_string()) => Value::Some(Box::new(Value::U64(70_000))),
+            Value::Str("b".to_string()) => Value::Seq(vec![
+                Value::I64(-129),
+                Value::I128(i64::MIN as i128 - 1),
+                Value::U128(u64::MAX as u128),
+                Value::F64(0.1),
+                Value::F64(1e300),
+                Value::F32(2.0),
+                Value::Str("1".to_string()),
+            ]),
+        });
+        v.tighten();
+
+        assert_eq!(
+            v,
+            Value::Map(indexmap! {
+                // Map keys are not changed.
+                Value::I64(1000) => Value::U8(0),
+                Value::Str("a".to_string()) => Value::Some(Box::new(Value::U32(70_000))),
+                Value::Str("b".to_string()) => Value::Seq(vec![
+                    Value::I16(-129),
+                    Value::I128(i64::MIN as i128 - 1),
+                    Value::U64(u64::MAX),
+                    Value::F64(0.1),
+                    Value::F64(1e300),
+                    Value::F32(2.0),
+                    Value::Str("1".to_string()),
+                ]),
+            })
+        );
+    }
+}
@@ -0,0 +1,345 @@
+use anyhow::anyhow;
+
+use crate::path::{parse_index, parse_pointer};
+use crate::{Error, Value};
+
+/// An operation of [RFC 6902](https://datatracker.ietf.org/doc/html/rfc6902) JSON patch.
+///
+/// Paths are [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901) JSON pointers
+/// resolved like [`Value::pointer`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOp {
+    /// Insert `value` at `path`, replacing the existing map entry or struct field.
+    ///
+    /// Seq elements are inserted before `path`, `-` appends to the end.
+    Add { path: String, value: Value },
+    /// Remove the map entry or seq element at `path`.
+    Remove { path: String },
+    /// Replace the existing value at `path` with `value`.
+    Replace { path: String, value: Value },
+    /// Remove the value at `from` and add it at `path`.
+    Move { from: String, path: String },
+    /// Add a copy of the value at `from` at `path`.
+    Copy { from: String, path: String },
+    /// Check that the value at `path` equals `value`.
+    ///
+    /// Values are compared with [`Value::structural_eq`], so `U16(80)` equals
+    /// `I64(80)` like JSON numbers do.
+    Test { path: String, value: Value },
+}
+
+impl Value {
+    /// Apply a [RFC 6902](https://datatracker.ietf.org/doc/html/rfc6902) JSON patch.
+    ///
+    /// Operations are applied in order. If any operation fails, including a
+    /// failed [`PatchOp::Test`], an error is returned and `self` is left untouched.
+    ///
+    /// `Value` can't represent everything JSON can, so some operations are rejected:
+    ///
+    /// - Fields can't be added to or removed from `Struct` and `StructVariant`,
+    ///   only replaced.
+    /// - Elements can't be added to or removed from `Tuple`, `TupleStruct` and
+    ///   `TupleVariant`, only replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{PatchOp, Value};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut v = Value::Seq(vec![Value::I32(1)]);
+    /// v.apply_json_patch(&[
+    ///     PatchOp::Test { path: "/0".to_string(), value: Value::I32(1) },
+    ///     PatchOp::Add { path: "/-".to_string(), value: Value::I32(2) },
+    /// ])?;
+    /// assert_eq!(v, Value::Seq(vec![Value::I32(1), Value::I32(2)]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn apply_json_patch(&mut self, patch: &[PatchOp]) -> Result<(), Error> {
+        let mut patched = self.clone();
+        for (idx, op) in patch.iter().enumerate() {
+            patched
+                .apply_patch_op(op)
+                .map_err(|e| Error(anyhow!("patch op {} failed: {}", idx, e)))?;
+        }
+        *self = patched;
+
+        Ok(())
+    }
+
+    fn apply_patch_op(&mut self, op: &PatchOp) -> Result<(), Error> {
+        match op {
+            PatchOp::Add { path, value } => self.patch_add(path, value.clone()),
+            PatchOp::Remove { path } => self.patch_remove(path).map(|_| ()),
+            PatchOp::Replace { path, value } => {
+                let target = self
+                    .pointer_mut(path)
+                    .ok_or_else(|| Error(anyhow!("path `{}` not exist", path)))?;
+                *target = value.clone();
+                Ok(())
+            }
+            PatchOp::Move { from, path } => {
+                if path.starts_with(from.as_str()) && path[from.len()..].starts_with('/') {
+                    return Err(Error(anyhow!(
+                        "can't move `{}` into its child `{}`",
+                        from,
+                        path
+                    )));
+                }
+                let value = self.patch_remove(from)?;
+                self.patch_add(path, value)
+            }
+            PatchOp::Copy { from, path } => {
+                let value = self
+                    .pointer(from)
+                    .ok_or_else(|| Error(anyhow!("path `{}` not exist", from)))?
+                    .clone();
+                self.patch_add(path, value)
+            }
+            PatchOp::Test { path, value } => match self.pointer(path) {
+                Some(v) if v.structural_eq(value) => Ok(()),
+                Some(v) => Err(Error(anyhow!(
+                    "test `{}` failed: expect {:?}, actual {:?}",
+                    path,
+                    value,
+                    v
+                ))),
+                None => Err(Error(anyhow!("path `{}` not exist", path))),
+            },
+        }
+    }
+
+    /// Resolve the parent of `pointer` and return it with the last token.
+    fn patch_parent(&mut self, pointer: &str) -> Result<(&mut Value, Option<String>), Error> {
+        let mut tokens = parse_pointer(pointer)?;
+        let last = tokens.pop();
+        let parent = tokens
+            .iter()
+            .try_fold(self, |v, token| v.get_token_mut(token))
+            .ok_or_else(|| Error(anyhow!("parent of `{}` not exist", pointer)))?;
+        Ok((parent, last))
+    }
+
+    fn patch_add(&mut self, pointer: &str, value: Value) -> Result<(), Error> {
+        let (parent, token) = self.patch_parent(pointer)?;
+        let token = match token {
+            Some(token) => token,
+            // The empty pointer replaces the whole value.
+            None => {
+                *parent = value;
+                return Ok(());
+            }
+        };
+
+        match parent.inner_mut() {
+            Value::Map(m) => {
+                m.insert(Value::Str(token), value);
+            }
+            Value::Seq(v) => {
+                let idx = if token == "-" {
+                    v.len()
+                } else {
+                    parse_index(&token)
+                        .filter(|idx| *idx <= v.len())
+                        .ok_or_else(|| Error(anyhow!("index `{}` out of range", token)))?
+                };
+                v.insert(idx, value);
+            }
+            Value::Struct(_, fields) | Value::StructVariant { fields, .. } => {
+                let field = fields
+                    .get_mut(token.as_str())
+                    .ok_or_else(|| Error(anyhow!("can't add field `{}` to struct", token)))?;
+                *field = value;
+            }
+            Value::Tuple(v) | Value::TupleStruct(_, v) | Value::TupleVariant { fields: v, .. } => {
+                let element = parse_index(&token)
+                    .and_then(|idx| v.get_mut(idx))
+                    .ok_or_else(|| Error(anyhow!("can't add element `{}` to tuple", token)))?;
+                *element = value;
+            }
+            v => return Err(Error(anyhow!("can't add into {:?}", v))),
+        }
+
+        Ok(())
+    }
+
+    fn patch_remove(&mut self, pointer: &str) -> Result<Value, Error> {
+        self.remove_path(pointer)
+            .ok_or_else(|| Error(anyhow!("can't remove `{}`", pointer)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    fn test_value() -> Value {
+        Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Str("web".to_string()),
+            Value::Str("ports".to_string()) => Value::Seq(vec![Value::U16(80), Value::U16(443)]),
+            Value::Str("tls".to_string()) => Value::Struct("Tls", indexmap! {
+                "enabled" => Value::Bool(false),
+            }),
+        })
+    }
+
+    fn s(v: &str) -> String {
+        v.to_string()
+    }
+
+    #[test]
+    fn test_add() -> anyhow::Result<()> {
+        let mut v = test_value();
+        v.apply_json_patch(&[
+            PatchOp::Add {
+                path: s("/ports/1"),
+                value: Value::U16(8080),
+            },
+            PatchOp::Add {
+                path: s("/ports/-"),
+                value: Value::U16(9090),
+            },
+            PatchOp::Add {
+                path: s("/host"),
+                value: Value::Str(s("localhost")),
+            },
+            PatchOp::Add {
+                path: s("/tls/enabled"),
+                value: Value::Bool(true),
+            },
+        ])?;
+        assert_eq!(
+            v.pointer("/ports"),
+            Some(&Value::Seq(vec![
+                Value::U16(80),
+                Value::U16(8080),
+                Value::U16(443),
+                Value::U16(9090)
+            ]))
+        );
+        assert_eq!(v.pointer("/host"), Some(&Value::Str(s("localhost"))));
+        assert_eq!(v.pointer("/tls/enabled"), Some(&Value::Bool(true)));
+
+        let err = v
+            .apply_json_patch(&[PatchOp::Add {
+                path: s("/tls/cert"),
+                value: Value::Unit,
+            }])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "patch op 0 failed: can't add field `cert` to struct"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_and_replace() -> anyhow::Result<()> {
+        let mut v = test_value();
+        v.apply_json_patch(&[
+            PatchOp::Remove {
+                path: s("/ports/0"),
+            },
+            PatchOp::Remove { path: s("/name") },
+            PatchOp::Replace {
+                path: s("/tls/enabled"),
+                value: Value::Bool(true),
+            },
+        ])?;
+        assert_eq!(
+            v,
+            Value::Map(indexmap! {
+                Value::Str(s("ports")) => Value::Seq(vec![Value::U16(443)]),
+                Value::Str(s("tls")) => Value::Struct("Tls", indexmap! {
+                    "enabled" => Value::Bool(true),
+                }),
+            })
+        );
+
+        assert!(v
+            .apply_json_patch(&[PatchOp::Replace {
+                path: s("/name"),
+                value: Value::Unit
+            }])
+            .is_err());
+        assert!(v
+            .apply_json_patch(&[PatchOp::Remove {
+                path: s("/tls/enabled")
+            }])
+            .is_err());
+        assert!(v
+            .apply_json_patch(&[PatchOp::Remove {
+                path: s("/ports/+0")
+            }])
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_move_and_copy() -> anyhow::Result<()> {
+        let mut v = test_value();
+        v.apply_json_patch(&[
+            PatchOp::Copy {
+                from: s("/ports/0"),
+                path: s("/http"),
+            },
+            PatchOp::Move {
+                from: s("/name"),
+                path: s("/ports/0"),
+            },
+        ])?;
+        assert_eq!(v.pointer("/http"), Some(&Value::U16(80)));
+        assert_eq!(v.pointer("/name"), None);
+        assert_eq!(
+            v.pointer("/ports"),
+            Some(&Value::Seq(vec![
+                Value::Str(s("web")),
+                Value::U16(80),
+                Value::U16(443)
+            ]))
+        );
+
+        assert!(v
+            .apply_json_patch(&[PatchOp::Move {
+                from: s("/ports"),
+                path: s("/ports/0")
+            }])
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_failed_test_aborts_patch() -> anyhow::Result<()> {
+        let mut v = test_value();
+        v.apply_json_patch(&[PatchOp::Test {
+            path: s("/name"),
+            value: Value::Str(s("web")),
+        }])?;
+        // Numbers compare by value, not by variant.
+        v.apply_json_patch(&[PatchOp::Test {
+            path: s("/ports/0"),
+            value: Value::I64(80),
+        }])?;
+
+        let err = v
+            .apply_json_patch(&[
+                PatchOp::Remove { path: s("/name") },
+                PatchOp::Test {
+                    path: s("/ports/0"),
+                    value: Value::U16(8080),
+                },
+                PatchOp::Remove { path: s("/ports") },
+            ])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "patch op 1 failed: test `/ports/0` failed: expect U16(8080), actual U16(80)"
+        );
+        // Operations before the failed test are rolled back.
+        assert_eq!(v, test_value());
+        Ok(())
+    }
+}
@@ -0,0 +1,391 @@
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use indexmap::IndexMap;
+
+use crate::{Error, Value};
+
+/// Split a [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901) JSON pointer into
+/// unescaped tokens.
+///
+/// The empty pointer `""` refers to the whole value and yields no tokens.
+pub(crate) fn parse_pointer(pointer: &str) -> Result<Vec<String>, Error> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    if !pointer.starts_with('/') {
+        return Err(Error(anyhow!(
+            "invalid pointer `{}`: must start with `/`",
+            pointer
+        )));
+    }
+
+    Ok(pointer[1..]
+        .split('/')
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+/// Escape a token to be appended to a JSON pointer.
+pub(crate) fn escape_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Parse a token as the index of a sequence.
+pub(crate) fn parse_index(token: &str) -> Option<usize> {
+    // RFC 6901 only allows digits without leading zeros, `parse` accepts `+1`.
+    if token.is_empty() || !token.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if token.len() > 1 && token.starts_with('0') {
+        return None;
+    }
+    token.parse().ok()
+}
+
+impl Value {
+    /// Look up a value by a [RFC 6901](https://datatracker.ietf.org/doc/html/rfc6901) JSON pointer.
+    ///
+    /// - `Map` is indexed by `Value::Str` keys.
+    /// - `Struct` and `StructVariant` are indexed by field names.
+    /// - `Seq`, `Tuple`, `TupleStruct` and `TupleVariant` are indexed by position.
+    /// - `Some`, `NewtypeStruct` and `NewtypeVariant` are transparent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{into_value, Value};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let v = into_value(vec![(1, "a"), (2, "b")])?;
+    /// assert_eq!(v.pointer("/1/1"), Some(&Value::Str("b".to_string())));
+    /// assert_eq!(v.pointer("/2"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pointer(&self, pointer: &str) -> Option<&Value> {
+        let tokens = parse_pointer(pointer).ok()?;
+        tokens.iter().try_fold(self, |v, token| v.get_token(token))
+    }
+
+    /// Mutable version of [`Value::pointer`].
+    pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value> {
+        let tokens = parse_pointer(pointer).ok()?;
+        tokens
+            .iter()
+            .try_fold(self, |v, token| v.get_token_mut(token))
+    }
+
+    /// Clone the value at the JSON pointer, see [`Value::pointer`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{into_value, Value};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let v = into_value(vec![vec![1u8, 2]])?;
+    /// assert_eq!(v.get_path("/0"), Some(Value::Seq(vec![Value::U8(1), Value::U8(2)])));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_path(&self, pointer: &str) -> Option<Value> {
+        self.pointer(pointer).cloned()
+    }
+
+    /// Detach and return the value at the JSON pointer.
+    ///
+    /// - `Map` entries are removed, keeping the order of other entries.
+    /// - `Seq` elements are removed, later elements are shifted to the front.
+    ///
+    /// Returns `None` if the pointer doesn't exist, points to the whole value, or
+    /// points into a struct, tuple or variant whose shape can't change.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{into_value, Value};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut v = into_value(vec![1u8, 2, 3])?;
+    /// assert_eq!(v.remove_path("/1"), Some(Value::U8(2)));
+    /// assert_eq!(v, Value::Seq(vec![Value::U8(1), Value::U8(3)]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remove_path(&mut self, pointer: &str) -> Option<Value> {
+        let mut tokens = parse_pointer(pointer).ok()?;
+        let last = tokens.pop()?;
+        let parent = tokens
+            .iter()
+            .try_fold(self, |v, token| v.get_token_mut(token))?;
+
+        match parent.inner_mut() {
+            Value::Map(m) => m.shift_remove(&Value::Str(last)),
+            Value::Seq(v) => {
+                let idx = parse_index(&last).filter(|idx| *idx < v.len())?;
+                Some(v.remove(idx))
+            }
+            _ => None,
+        }
+    }
+
+    /// Set value at the JSON pointer, creating missing intermediates as `Value::Map`.
+    ///
+    /// - Missing keys of `Map` will be inserted.
+    /// - Intermediate `Value::None` and `Value::Unit` will be replaced by an empty `Map`.
+    /// - Index of `Seq` that equals to its length (or `-`) will append a new element.
+    /// - `Struct` fields can only be replaced, not added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut v = Value::None;
+    /// v.set_path("/a/b", Value::Bool(true))?;
+    /// assert_eq!(v.pointer("/a/b"), Some(&Value::Bool(true)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_path(&mut self, pointer: &str, value: Value) -> Result<(), Error> {
+        let tokens = parse_pointer(pointer)?;
+
+        let mut current = self;
+        for (idx, token) in tokens.iter().enumerate() {
+            let last = idx + 1 == tokens.len();
+            current = current.get_or_insert_token(token, last).map_err(|e| {
+                Error(anyhow!(
+                    "set path `{}` failed at `{}`: {}",
+                    pointer,
+                    token,
+                    e
+                ))
+            })?;
+        }
+        *current = value;
+
+        Ok(())
+    }
+
+    /// Apply a batch of `(pointer, value)` overrides in order via [`Value::set_path`].
+    ///
+    /// Overrides are applied one by one, so later overrides win when two of them touch
+    /// the same path. An override that writes into a path replaced by an earlier scalar
+    /// override will fail. Overrides applied before the failed one are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut v = Value::None;
+    /// v.set_paths(&[("/a", Value::I64(1)), ("/b/c", Value::I64(2))])?;
+    /// assert_eq!(v.pointer("/b/c"), Some(&Value::I64(2)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_paths(&mut self, overrides: &[(&str, Value)]) -> Result<(), Error> {
+        for (idx, (pointer, value)) in overrides.iter().enumerate() {
+            self.set_path(pointer, value.clone())
+                .map_err(|e| Error(anyhow!("override {} failed: {}", idx, e)))?;
+        }
+
+        Ok(())
+    }
+
+    /// Unwrap transparent wrappers like `Some` and `NewtypeStruct`.
+    pub(crate) fn inner(&self) -> &Value {
+        match self {
+            Value::Some(v) => v.inner(),
+            Value::NewtypeStruct(_, v) => v.inner(),
+            Value::NewtypeVariant { value, .. } => value.inner(),
+            Value::Shared(v) => v.inner(),
+            v => v,
+        }
+    }
+
+    pub(crate) fn inner_mut(&mut self) -> &mut Value {
+        match self {
+            Value::Some(v) => v.inner_mut(),
+            Value::NewtypeStruct(_, v) => v.inner_mut(),
+            Value::NewtypeVariant { value, .. } => value.inner_mut(),
+            Value::Shared(v) => Arc::make_mut(v).inner_mut(),
+            v => v,
+        }
+    }
+
+    pub(crate) fn get_token(&self, token: &str) -> Option<&Value> {
+        match self.inner() {
+            Value::Map(m) => m.get(&Value::Str(token.to_string())),
+            Value::Struct(_, fields) | Value::StructVariant { fields, .. } => fields.get(token),
+            Value::Seq(v)
+            | Value::Tuple(v)
+            | Value::TupleStruct(_, v)
+            | Value::TupleVariant { fields: v, .. } => v.get(parse_index(token)?),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn get_token_mut(&mut self, token: &str) -> Option<&mut Value> {
+        match self.inner_mut() {
+            Value::Map(m) => m.get_mut(&Value::Str(token.to_string())),
+            Value::Struct(_, fields) | Value::StructVariant { fields, .. } => fields.get_mut(token),
+            Value::Seq(v)
+            | Value::Tuple(v)
+            | Value::TupleStruct(_, v)
+            | Value::TupleVariant { fields: v, .. } => v.get_mut(parse_index(token)?),
+            _ => None,
+        }
+    }
+
+    fn get_or_insert_token(&mut self, token: &str, last: bool) -> Result<&mut Value, Error> {
+        let current = self.inner_mut();
+        if matches!(current, Value::None | Value::Unit) {
+            *current = Value::Map(IndexMap::new());
+        }
+
+        match current {
+            Value::Map(m) => {
+                let placeholder = if last {
+                    Value::None
+                } else {
+                    Value::Map(IndexMap::new())
+                };
+                Ok(m.entry(Value::Str(token.to_string()))
+                    .or_insert(placeholder))
+            }
+            Value::Struct(_, fields) | Value::StructVariant { fields, .. } => fields
+                .get_mut(token)
+                .ok_or_else(|| Error(anyhow!("field not exist"))),
+            Value::Seq(v) => {
+                let idx = if token == "-" {
+                    v.len()
+                } else {
+                    parse_index(token).ok_or_else(|| Error(anyhow!("invalid index")))?
+                };
+                if idx == v.len() {
+                    v.push(if last {
+                        Value::None
+                    } else {
+                        Value::Map(IndexMap::new())
+                    });
+                }
+                v.get_mut(idx)
+                    .ok_or_else(|| Error(anyhow!("index out of range")))
+            }
+            Value::Tuple(v) | Value::TupleStruct(_, v) | Value::TupleVariant { fields: v, .. } => {
+                parse_index(token)
+                    .and_then(|idx| v.get_mut(idx))
+                    .ok_or_else(|| Error(anyhow!("index out of range")))
+            }
+            v => Err(Error(anyhow!("can't index into {:?}", v))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_get_and_remove_path() {
+        let mut v = Value::Map(indexmap! {
+            Value::Str("servers".to_string()) => Value::Seq(vec![
+                Value::Map(indexmap! {
+                    Value::Str("host".to_string()) => Value::Str("a".to_string()),
+                    Value::Str("port".to_string()) => Value::U16(80),
+                    Value::Str("tls".to_string()) => Value::Bool(false),
+                }),
+                Value::Str("b".to_string()),
+            ]),
+            Value::Str("meta".to_string()) => Value::Struct("Meta", indexmap! { "id" => Value::U8(1) }),
+        });
+
+        assert_eq!(v.get_path("/servers/0/port"), Some(Value::U16(80)));
+        assert_eq!(v.get_path("/servers/2"), None);
+
+        assert_eq!(v.remove_path("/servers/0/port"), Some(Value::U16(80)));
+        assert_eq!(
+            v.pointer("/servers/0"),
+            Some(&Value::Map(indexmap! {
+                Value::Str("host".to_string()) => Value::Str("a".to_string()),
+                Value::Str("tls".to_string()) => Value::Bool(false),
+            }))
+        );
+        assert_eq!(v.remove_path("/servers/0/port"), None);
+
+        let first = v.remove_path("/servers/0");
+        assert!(matches!(first, Some(Value::Map(_))));
+        assert_eq!(v.get_path("/servers/0"), Some(Value::Str("b".to_string())));
+
+        assert_eq!(v.remove_path("/meta/id"), None);
+        assert_eq!(v.remove_path(""), None);
+    }
+
+    #[test]
+    fn test_pointer() {
+        let v = Value::Struct(
+            "TestStruct",
+            indexmap! {
+                "a/b" => Value::Bool(true),
+                "f" => Value::Seq(vec![Value::U8(6), Value::U8(7)]),
+                "h" => Value::Map(indexmap! {
+                    Value::Str("a".to_string()) => Value::Some(Box::new(Value::F32(10.1))),
+                }),
+            },
+        );
+
+        assert_eq!(v.pointer(""), Some(&v));
+        assert_eq!(v.pointer("/a~1b"), Some(&Value::Bool(true)));
+        assert_eq!(v.pointer("/f/1"), Some(&Value::U8(7)));
+        assert_eq!(v.pointer("/f/01"), None);
+        assert_eq!(v.pointer("/f/+1"), None);
+        assert_eq!(v.pointer("/f/-1"), None);
+        assert_eq!(
+            v.pointer("/h/a"),
+            Some(&Value::Some(Box::new(Value::F32(10.1))))
+        );
+        assert_eq!(v.pointer("/x"), None);
+        assert_eq!(v.pointer("x"), None);
+    }
+
+    #[test]
+    fn test_set_paths() -> Result<()> {
+        let mut v = Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Str("base".to_string()),
+            Value::Str("ports".to_string()) => Value::Seq(vec![Value::U16(80)]),
+        });
+
+        v.set_paths(&[
+            ("/name", Value::Str("override".to_string())),
+            ("/ports/-", Value::U16(443)),
+            ("/log/level", Value::Str("debug".to_string())),
+            ("/log/level", Value::Str("info".to_string())),
+        ])?;
+
+        assert_eq!(
+            v.pointer("/name"),
+            Some(&Value::Str("override".to_string()))
+        );
+        assert_eq!(v.pointer("/ports/1"), Some(&Value::U16(443)));
+        assert_eq!(
+            v.pointer("/log/level"),
+            Some(&Value::Str("info".to_string()))
+        );
+
+        let err = v
+            .set_paths(&[("/a", Value::Bool(true)), ("/name/x", Value::Bool(true))])
+            .expect_err("must fail");
+        assert!(err.to_string().starts_with("override 1 failed"), "{}", err);
+        // Overrides before the failed one are kept.
+        assert_eq!(v.pointer("/a"), Some(&Value::Bool(true)));
+
+        Ok(())
+    }
+}
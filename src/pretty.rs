@@ -0,0 +1,299 @@
+use std::fmt::Write;
+
+use crate::transform::key_to_string;
+use crate::Value;
+
+/// How [`Value::to_pretty_string_with`] renders `Value::Bytes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BytesFormat {
+    /// Lowercase hex with a `0x` prefix, like `0x0a0b`.
+    #[default]
+    Hex,
+    /// Standard padded base64 wrapped as `base64"CgsM"`.
+    Base64,
+}
+
+impl Value {
+    /// Render value into an indented, multi-line, JSON-like string.
+    ///
+    /// Bytes are rendered as hex, use [`Value::to_pretty_string_with`] to pick
+    /// another format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{into_value, Value};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let v = into_value(vec![1, 2])?;
+    /// assert_eq!(v.to_pretty_string(2), "[\n  1,\n  2\n]");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_pretty_string(&self, indent: usize) -> String {
+        self.to_pretty_string_with(indent, BytesFormat::Hex)
+    }
+
+    /// Render value into an indented, multi-line, JSON-like string.
+    ///
+    /// The output doesn't expose rust type names:
+    ///
+    /// - `None`, `Unit` and unit structs are `null`.
+    /// - `Some`, newtype structs are rendered as their inner value.
+    /// - Unit variants are rendered as a string of the variant name.
+    /// - Other variants are rendered as a single entry object `{ "variant": payload }`.
+    /// - Tuples and tuple structs are arrays, structs are objects.
+    /// - Non-string map keys are rendered into strings.
+    pub fn to_pretty_string_with(&self, indent: usize, bytes: BytesFormat) -> String {
+        let mut buf = String::new();
+        write_pretty(&mut buf, self, indent, 0, bytes);
+        buf
+    }
+}
+
+fn write_pretty(buf: &mut String, v: &Value, indent: usize, level: usize, bytes: BytesFormat) {
+    match v {
+        Value::Bool(v) => write_display(buf, v),
+        Value::I8(v) => write_display(buf, v),
+        Value::I16(v) => write_display(buf, v),
+        Value::I32(v) => write_display(buf, v),
+        Value::I64(v) => write_display(buf, v),
+        Value::I128(v) => write_display(buf, v),
+        Value::U8(v) => write_display(buf, v),
+        Value::U16(v) => write_display(buf, v),
+        Value::U32(v) => write_display(buf, v),
+        Value::U64(v) => write_display(buf, v),
+        Value::U128(v) => write_display(buf, v),
+        Value::F32(v) => write_display(buf, v),
+        Value::F64(v) => write_display(buf, v),
+        Value::Char(v) => write_str(buf, v.encode_utf8(&mut [0; 4])),
+        Value::Str(v) => write_str(buf, v),
+        Value::Shared(v) => write_pretty(buf, v, indent, level, bytes),
+        Value::Bytes(v) => match bytes {
+            BytesFormat::Hex => {
+                buf.push_str("0x");
+                for b in v {
+                    let _ = write!(buf, "{:02x}", b);
+                }
+            }
+            BytesFormat::Base64 => {
+                buf.push_str("base64\"");
+                buf.push_str(&base64_encode(v));
+                buf.push('"');
+            }
+        },
+        Value::None | Value::Unit | Value::UnitStruct(_) => buf.push_str("null"),
+        Value::Some(v) | Value::NewtypeStruct(_, v) => write_pretty(buf, v, indent, level, bytes),
+        Value::UnitVariant { variant, .. } => write_str(buf, variant),
+        Value::NewtypeVariant { variant, value, .. } => {
+            write_entries(buf, [(*variant, &**value)], indent, level, bytes)
+        }
+        Value::Seq(v) | Value::Tuple(v) | Value::TupleStruct(_, v) => {
+            write_elements(buf, v, indent, level, bytes)
+        }
+        Value::TupleVariant {
+            variant, fields, ..
+        } => {
+            write_open(buf, '{', indent, level + 1);
+            write_str(buf, variant);
+            buf.push_str(": ");
+            write_elements(buf, fields, indent, level + 1, bytes);
+            write_close(buf, '}', indent, level);
+        }
+        Value::Map(m) => {
+            let keys: Vec<String> = m.keys().map(key_to_string).collect();
+            write_entries(
+                buf,
+                keys.iter().map(|k| k.as_str()).zip(m.values()),
+                indent,
+                level,
+                bytes,
+            )
+        }
+        Value::Struct(_, fields) => write_entries(
+            buf,
+            fields.iter().map(|(k, v)| (*k, v)),
+            indent,
+            level,
+            bytes,
+        ),
+        Value::StructVariant {
+            variant, fields, ..
+        } => {
+            write_open(buf, '{', indent, level + 1);
+            write_str(buf, variant);
+            buf.push_str(": ");
+            write_entries(
+                buf,
+                fields.iter().map(|(k, v)| (*k, v)),
+                indent,
+                level + 1,
+                bytes,
+            );
+            write_close(buf, '}', indent, level);
+        }
+    }
+}
+
+fn write_display(buf: &mut String, v: impl std::fmt::Display) {
+    let _ = write!(buf, "{}", v);
+}
+
+fn write_elements(buf: &mut String, v: &[Value], indent: usize, level: usize, bytes: BytesFormat) {
+    if v.is_empty() {
+        buf.push_str("[]");
+        return;
+    }
+
+    for (idx, v) in v.iter().enumerate() {
+        if idx == 0 {
+            write_open(buf, '[', indent, level + 1);
+        } else {
+            write_separator(buf, indent, level + 1);
+        }
+        write_pretty(buf, v, indent, level + 1, bytes);
+    }
+    write_close(buf, ']', indent, level);
+}
+
+fn write_entries<'a>(
+    buf: &mut String,
+    entries: impl IntoIterator<Item = (&'a str, &'a Value)>,
+    indent: usize,
+    level: usize,
+    bytes: BytesFormat,
+) {
+    let mut empty = true;
+    for (k, v) in entries {
+        if empty {
+            write_open(buf, '{', indent, level + 1);
+            empty = false;
+        } else {
+            write_separator(buf, indent, level + 1);
+        }
+        write_str(buf, k);
+        buf.push_str(": ");
+        write_pretty(buf, v, indent, level + 1, bytes);
+    }
+
+    if empty {
+        buf.push_str("{}");
+    } else {
+        write_close(buf, '}', indent, level);
+    }
+}
+
+fn write_open(buf: &mut String, c: char, indent: usize, level: usize) {
+    buf.push(c);
+    write_newline(buf, indent, level);
+}
+
+fn write_separator(buf: &mut String, indent: usize, level: usize) {
+    buf.push(',');
+    write_newline(buf, indent, level);
+}
+
+fn write_close(buf: &mut String, c: char, indent: usize, level: usize) {
+    write_newline(buf, indent, level);
+    buf.push(c);
+}
+
+fn write_newline(buf: &mut String, indent: usize, level: usize) {
+    buf.push('\n');
+    buf.extend(std::iter::repeat_n(' ', indent * level));
+}
+
+/// Write a JSON escaped string with quotes.
+fn write_str(buf: &mut String, s: &str) {
+    buf.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buf.push_str("\\\""),
+            '\\' => buf.push_str("\\\\"),
+            '\n' => buf.push_str("\\n"),
+            '\r' => buf.push_str("\\r"),
+            '\t' => buf.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(buf, "\\u{:04x}", c as u32);
+            }
+            c => buf.push(c),
+        }
+    }
+    buf.push('"');
+}
+
+/// Encode bytes into standard padded base64.
+pub(crate) fn base64_encode(input: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (idx, b)| n | (*b as u32) << (16 - 8 * idx));
+        for idx in 0..4 {
+            if idx <= chunk.len() {
+                out.push(TABLE[(n >> (18 - 6 * idx) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn test_pretty_variants() {
+        let v = Value::Seq(vec![
+            Value::Bytes(vec![10, 11, 12]),
+            Value::TupleVariant {
+                name: "E",
+                variant_index: 0,
+                variant: "T",
+                fields: vec![Value::Char('"'), Value::None],
+            },
+            Value::Map(indexmap! {
+                Value::U8(1) => Value::UnitVariant {
+                    name: "E",
+                    variant_index: 1,
+                    variant: "U",
+                },
+            }),
+            Value::Seq(vec![]),
+        ]);
+
+        assert_eq!(
+            v.to_pretty_string_with(1, BytesFormat::Base64),
+            r#"[
+ base64"CgsM",
+ {
+  "T": [
+   "\"",
+   null
+  ]
+ },
+ {
+  "1": "U"
+ },
+ []
+]"#
+        );
+        assert!(v.to_pretty_string(1).starts_with("[\n 0x0a0b0c,"));
+    }
+}
@@ -0,0 +1,388 @@
+use std::fmt::{Display, Formatter};
+
+use indexmap::IndexMap;
+
+use crate::path::escape_token;
+use crate::transform::key_to_string;
+use crate::{Value, ValueKind};
+
+/// A structural description of the expected shape of a [`Value`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schema {
+    /// Any value is accepted.
+    Any,
+    /// A scalar of the given kind.
+    Scalar(ValueKind),
+    /// `None`, `Unit` or a value matching the inner schema.
+    Optional(Box<Schema>),
+    /// A seq or tuple whose elements all match the inner schema.
+    Seq(Box<Schema>),
+    /// A map or struct with the given name and fields.
+    ///
+    /// Maps have an empty name.
+    Struct(String, IndexMap<String, Schema>),
+    /// A value matching any of the schemas.
+    Union(Vec<Schema>),
+}
+
+impl Schema {
+    /// The kind that values must have to match this schema.
+    fn expected_kind(&self) -> Option<ValueKind> {
+        match self {
+            Schema::Any => None,
+            Schema::Scalar(kind) => Some(*kind),
+            Schema::Optional(s) => s.expected_kind(),
+            Schema::Seq(_) => Some(ValueKind::Seq),
+            Schema::Struct(_, _) => Some(ValueKind::Struct),
+            Schema::Union(v) => v.iter().find_map(|s| s.expected_kind()),
+        }
+    }
+
+    /// Merge schemas of elements of the same seq.
+    ///
+    /// Distinct schemas become a [`Schema::Union`] in the order they first appear.
+    /// Nulls are folded into other schemas as [`Schema::Optional`].
+    fn merge(schemas: impl IntoIterator<Item = Schema>) -> Schema {
+        let null = Schema::Optional(Box::new(Schema::Any));
+
+        let mut distinct: Vec<Schema> = Vec::new();
+        for s in schemas {
+            if !distinct.contains(&s) {
+                distinct.push(s)
+            }
+        }
+        let nullable = distinct.len() > 1 && distinct.contains(&null);
+        if nullable {
+            distinct.retain(|s| s != &null);
+        }
+
+        let merged = match distinct.len() {
+            0 => Schema::Any,
+            1 => distinct.pop().expect("must have one schema"),
+            _ => Schema::Union(distinct),
+        };
+        match merged {
+            Schema::Optional(_) => merged,
+            s if nullable => Schema::Optional(Box::new(s)),
+            s => s,
+        }
+    }
+}
+
+/// A value whose kind conflicts with its [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    /// JSON pointer of the value.
+    pub path: String,
+    /// The kind required by the schema.
+    pub expected: ValueKind,
+    /// The kind of the value.
+    pub found: ValueKind,
+}
+
+impl Display for SchemaError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}`: expected {}, found {}",
+            self.path, self.expected, self.found
+        )
+    }
+}
+
+impl Value {
+    /// Describe the structure of this value.
+    ///
+    /// - Scalars, bytes, units and unit variants become [`Schema::Scalar`].
+    /// - `None` and `Unit` become `Schema::Optional(Schema::Any)`, `Some` becomes
+    ///   [`Schema::Optional`] of its inner value.
+    /// - Newtype structs and newtype variants are transparent.
+    /// - Seqs, tuples and tuple variants become [`Schema::Seq`]. The element schema
+    ///   is inferred from all elements: [`Schema::Any`] if empty, a
+    ///   [`Schema::Union`] if they differ. Nulls are merged into the others as
+    ///   [`Schema::Optional`].
+    /// - Maps, structs and struct variants become [`Schema::Struct`], keyed by
+    ///   the string form of their keys. Maps have an empty name, struct variants
+    ///   use the variant name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{Schema, Value, ValueKind};
+    ///
+    /// let v = Value::Seq(vec![Value::I64(1), Value::None]);
+    /// assert_eq!(
+    ///     v.infer_schema(),
+    ///     Schema::Seq(Box::new(Schema::Optional(Box::new(Schema::Scalar(ValueKind::I64)))))
+    /// );
+    /// ```
+    pub fn infer_schema(&self) -> Schema {
+        match self {
+            Value::None | Value::Unit => Schema::Optional(Box::new(Schema::Any)),
+            Value::Some(v) => match v.infer_schema() {
+                s @ Schema::Optional(_) => s,
+                s => Schema::Optional(Box::new(s)),
+            },
+            Value::NewtypeStruct(_, v) => v.infer_schema(),
+            Value::Shared(v) => v.infer_schema(),
+            Value::NewtypeVariant { value, .. } => value.infer_schema(),
+            Value::Seq(v)
+            | Value::Tuple(v)
+            | Value::TupleStruct(_, v)
+            | Value::TupleVariant { fields: v, .. } => {
+                Schema::Seq(Box::new(Schema::merge(v.iter().map(|v| v.infer_schema()))))
+            }
+            Value::Map(m) => Schema::Struct(
+                String::new(),
+                m.iter()
+                    .map(|(k, v)| (key_to_string(k), v.infer_schema()))
+                    .collect(),
+            ),
+            Value::Struct(name, fields)
+            | Value::StructVariant {
+                variant: name,
+                fields,
+                ..
+            } => Schema::Struct(
+                name.to_string(),
+                fields
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.infer_schema()))
+                    .collect(),
+            ),
+            v => Schema::Scalar(v.kind()),
+        }
+    }
+
+    /// Collect every value whose kind conflicts with `schema`.
+    ///
+    /// Kinds are matched with the same rules as deserializing:
+    ///
+    /// - `Some`, newtype structs and newtype variants are transparent.
+    /// - Integers match any integer kind whose range contains them.
+    /// - `F32` and `F64` match both float kinds.
+    /// - `Schema::Seq` matches seqs and tuples, `Schema::Struct` matches maps and
+    ///   structs of any name.
+    /// - `Schema::Union` matches if any of its schemas matches, conflicts are
+    ///   reported with the kind of its first schema.
+    /// - Other kinds must be the same.
+    ///
+    /// Checking continues after a conflict, so every mistyped field is reported.
+    /// Children of a conflicting value are not checked. Missing fields and fields
+    /// not listed in the schema are not type errors and are not reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::indexmap;
+    /// use serde_bridge::{Schema, Value, ValueKind};
+    ///
+    /// let schema = Schema::Struct("Http".to_string(), indexmap! {
+    ///     "port".to_string() => Schema::Scalar(ValueKind::U16),
+    /// });
+    /// let v = Value::Map(indexmap! {
+    ///     Value::Str("port".to_string()) => Value::Str("80".to_string()),
+    /// });
+    ///
+    /// let errors = v.collect_type_errors(&schema);
+    /// assert_eq!(errors[0].to_string(), "`/port`: expected u16, found str");
+    /// ```
+    pub fn collect_type_errors(&self, schema: &Schema) -> Vec<SchemaError> {
+        let mut errors = Vec::new();
+        self.check_schema(schema, String::new(), &mut errors);
+        errors
+    }
+
+    fn check_schema(&self, schema: &Schema, path: String, errors: &mut Vec<SchemaError>) {
+        let v = match schema {
+            Schema::Any => return,
+            Schema::Union(schemas) => {
+                let matched = schemas.iter().any(|s| {
+                    let mut errors = Vec::new();
+                    self.check_schema(s, path.clone(), &mut errors);
+                    errors.is_empty()
+                });
+                if !matched {
+                    errors.push(SchemaError {
+                        expected: schema.expected_kind().unwrap_or(ValueKind::Unit),
+                        found: self.inner().kind(),
+                        path,
+                    })
+                }
+                return;
+            }
+            Schema::Optional(s) => match self.inner() {
+                Value::None | Value::Unit => return,
+                v => return v.check_schema(s, path, errors),
+            },
+            _ => self.inner(),
+        };
+
+        let matched = match (schema, v) {
+            (Schema::Scalar(kind), v) => scalar_matches(*kind, v),
+            (
+                Schema::Seq(s),
+                Value::Seq(elements)
+                | Value::Tuple(elements)
+                | Value::TupleStruct(_, elements)
+                | Value::TupleVariant {
+                    fields: elements, ..
+                },
+            ) => {
+                for (idx, element) in elements.iter().enumerate() {
+                    element.check_schema(s, format!("{}/{}", path, idx), errors)
+                }
+                true
+            }
+            (
+                Schema::Struct(_, fields),
+                Value::Map(_) | Value::Struct(_, _) | Value::StructVariant { .. },
+            ) => {
+                for (name, s) in fields {
+                    if let Some(field) = v.get_token(name) {
+                        field.check_schema(s, format!("{}/{}", path, escape_token(name)), errors)
+                    }
+                }
+                true
+            }
+            _ => false,
+        };
+
+        if !matched {
+            errors.push(SchemaError {
+                path,
+                expected: schema.expected_kind().expect("must not be any"),
+                found: v.kind(),
+            })
+        }
+    }
+}
+
+/// Check scalar `v` against `kind` like deserializing would.
+fn scalar_matches(kind: ValueKind, v: &Value) -> bool {
+    if kind.is_float() {
+        return v.kind().is_float();
+    }
+    if !kind.is_integer() {
+        return kind == v.kind();
+    }
+    if !v.kind().is_integer() {
+        return false;
+    }
+
+    match (v.to_i128(), v.to_u128()) {
+        (Some(i), _) => match kind {
+            ValueKind::I8 => i8::try_from(i).is_ok(),
+            ValueKind::I16 => i16::try_from(i).is_ok(),
+            ValueKind::I32 => i32::try_from(i).is_ok(),
+            ValueKind::I64 => i64::try_from(i).is_ok(),
+            ValueKind::I128 => true,
+            ValueKind::U8 => u8::try_from(i).is_ok(),
+            ValueKind::U16 => u16::try_from(i).is_ok(),
+            ValueKind::U32 => u32::try_from(i).is_ok(),
+            ValueKind::U64 => u64::try_from(i).is_ok(),
+            ValueKind::U128 => u128::try_from(i).is_ok(),
+            _ => unreachable!("must be integer kind"),
+        },
+        // Only values above `i128::MAX` can't be represented as `i128`.
+        _ => kind == ValueKind::U128,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_infer_schema() {
+        let v = Value::Map(indexmap! {
+            Value::Str("ports".to_string()) => Value::Seq(vec![Value::U16(80), Value::None, Value::U16(443)]),
+            Value::Str("mixed".to_string()) => Value::Tuple(vec![Value::Str("a".to_string()), Value::Bool(true)]),
+            Value::Str("empty".to_string()) => Value::Seq(vec![]),
+            Value::Str("nulls".to_string()) => Value::Seq(vec![Value::Unit]),
+        });
+        let schema = v.infer_schema();
+        assert_eq!(
+            schema,
+            Schema::Struct(
+                "".to_string(),
+                indexmap! {
+                    "ports".to_string() => Schema::Seq(Box::new(Schema::Optional(Box::new(Schema::Scalar(ValueKind::U16))))),
+                    "mixed".to_string() => Schema::Seq(Box::new(Schema::Union(vec![
+                        Schema::Scalar(ValueKind::Str),
+                        Schema::Scalar(ValueKind::Bool),
+                    ]))),
+                    "empty".to_string() => Schema::Seq(Box::new(Schema::Any)),
+                    "nulls".to_string() => Schema::Seq(Box::new(Schema::Optional(Box::new(Schema::Any)))),
+                }
+            )
+        );
+
+        // Values always match their own schema.
+        assert!(v.collect_type_errors(&schema).is_empty());
+        let other = Value::Map(indexmap! {
+            Value::Str("mixed".to_string()) => Value::Seq(vec![Value::I8(1)]),
+        });
+        assert_eq!(
+            other.collect_type_errors(&schema),
+            vec![SchemaError {
+                path: "/mixed/0".to_string(),
+                expected: ValueKind::Str,
+                found: ValueKind::I8,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_collect_type_errors() {
+        let schema = Schema::Struct(
+            "Config".to_string(),
+            indexmap! {
+                "name".to_string() => Schema::Scalar(ValueKind::Str),
+                "port".to_string() => Schema::Scalar(ValueKind::U16),
+                "tags".to_string() => Schema::Optional(Box::new(Schema::Seq(Box::new(Schema::Scalar(ValueKind::Str))))),
+            },
+        );
+
+        let v = Value::Struct(
+            "Config",
+            indexmap! {
+                "name" => Value::Str("web".to_string()),
+                "port" => Value::I64(70000),
+                "tags" => Value::Some(Box::new(Value::Seq(vec![
+                    Value::Str("a".to_string()),
+                    Value::Bool(true),
+                ]))),
+            },
+        );
+        assert_eq!(
+            v.collect_type_errors(&schema),
+            vec![
+                SchemaError {
+                    path: "/port".to_string(),
+                    expected: ValueKind::U16,
+                    found: ValueKind::I64,
+                },
+                SchemaError {
+                    path: "/tags/1".to_string(),
+                    expected: ValueKind::Str,
+                    found: ValueKind::Bool,
+                },
+            ]
+        );
+
+        let v = Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Str("web".to_string()),
+            Value::Str("port".to_string()) => Value::U64(80),
+            Value::Str("tags".to_string()) => Value::None,
+        });
+        assert!(v.collect_type_errors(&schema).is_empty());
+
+        assert_eq!(
+            Value::Seq(vec![]).collect_type_errors(&schema)[0].to_string(),
+            "``: expected struct, found seq"
+        );
+    }
+}
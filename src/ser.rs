@@ -5,7 +5,7 @@ use serde::ser::{
 };
 use serde::{ser, Serialize};
 
-use crate::{Error, Value};
+use crate::{Config, Error, NanHandling, Value};
 
 /// Convert `T: Serialize` into [`Value`].
 ///
@@ -58,7 +58,78 @@ impl serde::Serialize for Value {
     where
         S: serde::Serializer,
     {
-        match self {
+        self.with_config(Config::default()).serialize(s)
+    }
+}
+
+impl Value {
+    /// Serialize [`Value`] with given [`Config`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{Config, Value};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()>{
+    /// let v = Value::NewtypeStruct("Millimeters", Box::new(Value::U8(1)));
+    /// let config = Config::default().newtype_transparent(false);
+    /// let s = serde_json::to_string(&v.with_config(config))?;
+    /// assert_eq!(s, r#"{"Millimeters":1}"#);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_config(&self, config: Config) -> ConfiguredValue<'_> {
+        ConfiguredValue {
+            value: self,
+            config,
+        }
+    }
+}
+
+/// ConfiguredValue is a [`Value`] reference that serializes with [`Config`].
+///
+/// Created by [`Value::with_config`].
+///
+/// Nested values are wrapped by reference too, so serializing never clones the
+/// underlying [`Value`].
+pub struct ConfiguredValue<'a> {
+    value: &'a Value,
+    config: Config,
+}
+
+impl ConfiguredValue<'_> {
+    fn wrap<'b>(&self, value: &'b Value) -> ConfiguredValue<'b> {
+        value.with_config(self.config)
+    }
+
+    /// Serialize `NaN` and infinities following [`Config::nan_handling`].
+    ///
+    /// `v` is widened from `f32` if needed, which keeps non-finite values as is.
+    fn serialize_non_finite<S>(&self, v: f64, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.config.nan_handling {
+            NanHandling::Keep => match self.value {
+                Value::F32(v) => s.serialize_f32(*v),
+                _ => s.serialize_f64(v),
+            },
+            NanHandling::Error => Err(ser::Error::custom(format!(
+                "non-finite float {} is not allowed",
+                v
+            ))),
+            NanHandling::Null => s.serialize_none(),
+            NanHandling::String => s.serialize_str(&v.to_string()),
+        }
+    }
+}
+
+impl serde::Serialize for ConfiguredValue<'_> {
+    fn serialize<S>(&self, s: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.value {
             Value::Bool(v) => s.serialize_bool(*v),
             Value::I8(v) => s.serialize_i8(*v),
             Value::I16(v) => s.serialize_i16(*v),
@@ -70,13 +141,16 @@ impl serde::Serialize for Value {
             Value::U32(v) => s.serialize_u32(*v),
             Value::U64(v) => s.serialize_u64(*v),
             Value::U128(v) => s.serialize_u128(*v),
+            Value::F32(v) if !v.is_finite() => self.serialize_non_finite(*v as f64, s),
             Value::F32(v) => s.serialize_f32(*v),
+            Value::F64(v) if !v.is_finite() => self.serialize_non_finite(*v, s),
             Value::F64(v) => s.serialize_f64(*v),
             Value::Char(v) => s.serialize_char(*v),
             Value::Str(v) => s.serialize_str(v),
             Value::Bytes(v) => s.serialize_bytes(v),
             Value::None => s.serialize_none(),
-            Value::Some(v) => s.serialize_some(v),
+            Value::Some(v) => s.serialize_some(&self.wrap(v)),
+            Value::Shared(v) => self.wrap(v).serialize(s),
             Value::Unit => s.serialize_unit(),
             Value::UnitStruct(name) => s.serialize_unit_struct(name),
             Value::UnitVariant {
@@ -84,31 +158,39 @@ impl serde::Serialize for Value {
                 variant_index,
                 variant,
             } => s.serialize_unit_variant(name, *variant_index, variant),
-            Value::NewtypeStruct(name, value) => s.serialize_newtype_struct(name, value),
+            Value::NewtypeStruct(name, value) => {
+                if self.config.newtype_transparent {
+                    s.serialize_newtype_struct(name, &self.wrap(value))
+                } else {
+                    let mut se = s.serialize_map(Some(1))?;
+                    se.serialize_entry(name, &self.wrap(value))?;
+                    se.end()
+                }
+            }
             Value::NewtypeVariant {
                 name,
                 variant_index,
                 variant,
                 value,
-            } => s.serialize_newtype_variant(name, *variant_index, variant, value),
+            } => s.serialize_newtype_variant(name, *variant_index, variant, &self.wrap(value)),
             Value::Seq(v) => {
                 let mut seq = s.serialize_seq(Some(v.len()))?;
                 for i in v {
-                    seq.serialize_element(i)?;
+                    seq.serialize_element(&self.wrap(i))?;
                 }
                 seq.end()
             }
             Value::Tuple(v) => {
                 let mut tuple = s.serialize_tuple(v.len())?;
                 for i in v {
-                    tuple.serialize_element(i)?;
+                    tuple.serialize_element(&self.wrap(i))?;
                 }
                 tuple.end()
             }
             Value::TupleStruct(name, fields) => {
                 let mut se = s.serialize_tuple_struct(name, fields.len())?;
                 for i in fields {
-                    se.serialize_field(i)?;
+                    se.serialize_field(&self.wrap(i))?;
                 }
                 se.end()
             }
@@ -121,21 +203,21 @@ impl serde::Serialize for Value {
                 let mut se =
                     s.serialize_tuple_variant(name, *variant_index, variant, fields.len())?;
                 for i in fields {
-                    se.serialize_field(i)?;
+                    se.serialize_field(&self.wrap(i))?;
                 }
                 se.end()
             }
             Value::Map(map) => {
                 let mut se = s.serialize_map(Some(map.len()))?;
                 for (k, v) in map {
-                    se.serialize_entry(k, v)?;
+                    se.serialize_entry(&self.wrap(k), &self.wrap(v))?;
                 }
                 se.end()
             }
             Value::Struct(name, fields) => {
                 let mut se = s.serialize_struct(name, fields.len())?;
                 for (k, v) in fields {
-                    se.serialize_field(k, v)?;
+                    se.serialize_field(k, &self.wrap(v))?;
                 }
                 se.end()
             }
@@ -148,7 +230,7 @@ impl serde::Serialize for Value {
                 let mut se =
                     s.serialize_struct_variant(name, *variant_index, variant, fields.len())?;
                 for (k, v) in fields {
-                    se.serialize_field(k, v)?;
+                    se.serialize_field(k, &self.wrap(v))?;
                 }
                 se.end()
             }
@@ -156,7 +238,23 @@ impl serde::Serialize for Value {
     }
 }
 
-struct Serializer;
+/// Serializer is a [`serde::Serializer`] that converts `T: Serialize` into [`Value`].
+///
+/// It can be passed to any API that accepts `impl serde::Serializer`.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Serialize;
+/// use serde_bridge::{Serializer, Value};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()>{
+/// let v = true.serialize(Serializer)?;
+/// assert_eq!(v, Value::Bool(true));
+/// # Ok(())
+/// # }
+/// ```
+pub struct Serializer;
 
 impl serde::Serializer for Serializer {
     type Ok = Value;
@@ -229,11 +327,14 @@ impl serde::Serializer for Serializer {
         Ok(Value::None)
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        Ok(Value::Some(Box::new(value.serialize(Serializer)?)))
+        let value = value
+            .serialize(Serializer)
+            .map_err(|e| e.context("while serializing some"))?;
+        Ok(Value::Some(Box::new(value)))
     }
 
     fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
@@ -257,21 +358,21 @@ impl serde::Serializer for Serializer {
         })
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
         name: &'static str,
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        Ok(Value::NewtypeStruct(
-            name,
-            Box::new(value.serialize(Serializer)?),
-        ))
+        let value = value
+            .serialize(Serializer)
+            .map_err(|e| e.context(format!("while serializing newtype struct `{}`", name)))?;
+        Ok(Value::NewtypeStruct(name, Box::new(value)))
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         name: &'static str,
         variant_index: u32,
@@ -279,13 +380,19 @@ impl serde::Serializer for Serializer {
         value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
+        let value = value.serialize(Serializer).map_err(|e| {
+            e.context(format!(
+                "while serializing newtype variant `{}::{}`",
+                name, variant
+            ))
+        })?;
         Ok(Value::NewtypeVariant {
             name,
             variant_index,
             variant,
-            value: Box::new(value.serialize(Serializer)?),
+            value: Box::new(value),
         })
     }
 
@@ -348,7 +455,7 @@ impl serde::Serializer for Serializer {
     }
 }
 
-struct SeqSerializer {
+pub struct SeqSerializer {
     elements: Vec<Value>,
 }
 
@@ -364,11 +471,16 @@ impl ser::SerializeSeq for SeqSerializer {
     type Ok = Value;
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        self.elements.push(value.serialize(Serializer)?);
+        let idx = self.elements.len();
+        self.elements.push(
+            value
+                .serialize(Serializer)
+                .map_err(|e| e.context(format!("while serializing element {} of seq", idx)))?,
+        );
 
         Ok(())
     }
@@ -378,7 +490,7 @@ impl ser::SerializeSeq for SeqSerializer {
     }
 }
 
-struct TupleSerializer {
+pub struct TupleSerializer {
     elements: Vec<Value>,
 }
 
@@ -394,11 +506,16 @@ impl ser::SerializeTuple for TupleSerializer {
     type Ok = Value;
     type Error = Error;
 
-    fn serialize_element<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        self.elements.push(value.serialize(Serializer)?);
+        let idx = self.elements.len();
+        self.elements.push(
+            value
+                .serialize(Serializer)
+                .map_err(|e| e.context(format!("while serializing element {} of tuple", idx)))?,
+        );
 
         Ok(())
     }
@@ -408,7 +525,7 @@ impl ser::SerializeTuple for TupleSerializer {
     }
 }
 
-struct TupleStructSerializer {
+pub struct TupleStructSerializer {
     name: &'static str,
     fields: Vec<Value>,
 }
@@ -426,11 +543,17 @@ impl ser::SerializeTupleStruct for TupleStructSerializer {
     type Ok = Value;
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        self.fields.push(value.serialize(Serializer)?);
+        let (idx, name) = (self.fields.len(), self.name);
+        self.fields.push(value.serialize(Serializer).map_err(|e| {
+            e.context(format!(
+                "while serializing field {} of tuple struct `{}`",
+                idx, name
+            ))
+        })?);
 
         Ok(())
     }
@@ -440,7 +563,7 @@ impl ser::SerializeTupleStruct for TupleStructSerializer {
     }
 }
 
-struct TupleVariantSerializer {
+pub struct TupleVariantSerializer {
     name: &'static str,
     variant_index: u32,
     variant: &'static str,
@@ -462,11 +585,17 @@ impl ser::SerializeTupleVariant for TupleVariantSerializer {
     type Ok = Value;
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        self.fields.push(value.serialize(Serializer)?);
+        let (idx, name, variant) = (self.fields.len(), self.name, self.variant);
+        self.fields.push(value.serialize(Serializer).map_err(|e| {
+            e.context(format!(
+                "while serializing field {} of tuple variant `{}::{}`",
+                idx, name, variant
+            ))
+        })?);
 
         Ok(())
     }
@@ -481,7 +610,7 @@ impl ser::SerializeTupleVariant for TupleVariantSerializer {
     }
 }
 
-struct MapSerializer {
+pub struct MapSerializer {
     cache_key: Option<Value>,
     entries: IndexMap<Value, Value>,
 }
@@ -499,28 +628,34 @@ impl ser::SerializeMap for MapSerializer {
     type Ok = Value;
     type Error = Error;
 
-    fn serialize_key<T: ?Sized>(&mut self, key: &T) -> Result<(), Self::Error>
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         debug_assert!(
             self.cache_key.is_none(),
             "value for the last entry is missing"
         );
-        self.cache_key = Some(key.serialize(Serializer)?);
+        self.cache_key = Some(
+            key.serialize(Serializer)
+                .map_err(|e| e.context("while serializing key of map"))?,
+        );
 
         Ok(())
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, value: &T) -> Result<(), Self::Error>
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
         let key = self
             .cache_key
             .take()
             .expect("key for current entry is missing");
-        self.entries.insert(key, value.serialize(Serializer)?);
+        let value = value
+            .serialize(Serializer)
+            .map_err(|e| e.context(format!("while serializing value of map key {:?}", key)))?;
+        self.entries.insert(key, value);
 
         Ok(())
     }
@@ -530,7 +665,7 @@ impl ser::SerializeMap for MapSerializer {
     }
 }
 
-struct StructSerializer {
+pub struct StructSerializer {
     name: &'static str,
     fields: IndexMap<&'static str, Value>,
 }
@@ -548,15 +683,20 @@ impl ser::SerializeStruct for StructSerializer {
     type Ok = Value;
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        self.fields.insert(key, value.serialize(Serializer)?);
+        let name = self.name;
+        self.fields.insert(
+            key,
+            value.serialize(Serializer).map_err(|e| {
+                e.context(format!(
+                    "while serializing field `{}` of struct `{}`",
+                    key, name
+                ))
+            })?,
+        );
 
         Ok(())
     }
@@ -566,7 +706,7 @@ impl ser::SerializeStruct for StructSerializer {
     }
 }
 
-struct StructVariantSerializer {
+pub struct StructVariantSerializer {
     name: &'static str,
     variant_index: u32,
     variant: &'static str,
@@ -588,15 +728,20 @@ impl ser::SerializeStructVariant for StructVariantSerializer {
     type Ok = Value;
     type Error = Error;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
     where
-        T: Serialize,
+        T: ?Sized + Serialize,
     {
-        self.fields.insert(key, value.serialize(Serializer)?);
+        let (name, variant) = (self.name, self.variant);
+        self.fields.insert(
+            key,
+            value.serialize(Serializer).map_err(|e| {
+                e.context(format!(
+                    "while serializing field `{}` of struct variant `{}::{}`",
+                    key, name, variant
+                ))
+            })?,
+        );
 
         Ok(())
     }
@@ -678,4 +823,97 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_serialize_newtype_struct() -> Result<()> {
+        let value = Value::NewtypeStruct("Millimeters", Box::new(Value::U8(1)));
+
+        assert_eq!(serde_json::to_string(&value)?, "1");
+        assert_eq!(
+            serde_json::to_string(&value.with_config(Config::default()))?,
+            "1"
+        );
+        assert_eq!(
+            serde_json::to_string(
+                &value.with_config(Config::default().newtype_transparent(false))
+            )?,
+            r#"{"Millimeters":1}"#
+        );
+
+        // Config should be applied to nested values too.
+        let value = Value::Seq(vec![value]);
+        assert_eq!(
+            serde_json::to_string(
+                &value.with_config(Config::default().newtype_transparent(false))
+            )?,
+            r#"[{"Millimeters":1}]"#
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serialize_error_breadcrumb() {
+        struct Failing;
+
+        impl serde::Serialize for Failing {
+            fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                Err(ser::Error::custom("failing field"))
+            }
+        }
+
+        #[derive(serde::Serialize)]
+        struct TestStruct {
+            a: bool,
+            h: Failing,
+        }
+
+        let err = into_value(TestStruct {
+            a: true,
+            h: Failing,
+        })
+        .expect_err("must fail");
+        assert_eq!(
+            err.to_string(),
+            "while serializing field `h` of struct `TestStruct`: failing field"
+        );
+
+        let err = into_value(vec![Some(TestStruct {
+            a: true,
+            h: Failing,
+        })])
+        .expect_err("must fail");
+        assert_eq!(
+            err.to_string(),
+            "while serializing element 0 of seq: while serializing some: \
+             while serializing field `h` of struct `TestStruct`: failing field"
+        );
+    }
+
+    #[test]
+    fn test_nan_handling() -> Result<()> {
+        let to_json = |v: &Value, h: NanHandling| {
+            serde_json::to_string(&v.with_config(Config::new().nan_handling(h)))
+        };
+
+        let nan = Value::F64(f64::NAN);
+        assert_eq!(to_json(&nan, NanHandling::Keep)?, "null");
+        assert_eq!(to_json(&nan, NanHandling::Null)?, "null");
+        assert_eq!(to_json(&nan, NanHandling::String)?, r#""NaN""#);
+        let err = to_json(&nan, NanHandling::Error).unwrap_err();
+        assert_eq!(err.to_string(), "non-finite float NaN is not allowed");
+
+        let v = Value::Seq(vec![
+            Value::F32(f32::INFINITY),
+            Value::F64(f64::NEG_INFINITY),
+            Value::F64(1.5),
+        ]);
+        assert_eq!(to_json(&v, NanHandling::String)?, r#"["inf","-inf",1.5]"#);
+        assert!(to_json(&v, NanHandling::Error).is_err());
+        assert_eq!(to_json(&Value::F64(1.5), NanHandling::Error)?, "1.5");
+        Ok(())
+    }
 }
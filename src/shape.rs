@@ -0,0 +1,171 @@
+use std::hash::Hash;
+
+use indexmap::IndexMap;
+
+use crate::Value;
+
+impl Value {
+    /// Check whether two values share the same structure, ignoring scalar contents.
+    ///
+    /// - Numbers are compared as a single kind: `I32(1)` has the same shape as
+    ///   `U64(2)` or `F64(0.5)`, so width differences introduced by formats don't matter.
+    /// - Other scalars (`Bool`, `Char`, `Str`, `Bytes`) must have the same variant.
+    /// - Sequences must have the same length and element-wise same shapes.
+    /// - Maps and structs must have the same key sets (order is ignored) and
+    ///   same-shaped values. Container and variant names must match.
+    /// - `Some` only matches `Some`, `None` only matches `None`.
+    /// - `Shared` is transparent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{into_value, Value};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// assert!(into_value(vec![1u8, 2])?.same_shape(&into_value(vec![3i64, 4])?));
+    /// assert!(!into_value(vec![1u8, 2])?.same_shape(&into_value(vec![3u8])?));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn same_shape(&self, other: &Value) -> bool {
+        use Value::*;
+
+        match (self, other) {
+            (Shared(a), b) | (b, Shared(a)) => a.same_shape(b),
+            (a, b) if a.is_number() && b.is_number() => true,
+            (Bool(_), Bool(_)) | (Char(_), Char(_)) | (Str(_), Str(_)) | (Bytes(_), Bytes(_)) => {
+                true
+            }
+            (None, None) | (Unit, Unit) => true,
+            (Some(a), Some(b)) => a.same_shape(b),
+            (UnitStruct(a), UnitStruct(b)) => a == b,
+            (
+                UnitVariant {
+                    name: an,
+                    variant: av,
+                    ..
+                },
+                UnitVariant {
+                    name: bn,
+                    variant: bv,
+                    ..
+                },
+            ) => an == bn && av == bv,
+            (NewtypeStruct(an, a), NewtypeStruct(bn, b)) => an == bn && a.same_shape(b),
+            (
+                NewtypeVariant {
+                    name: an,
+                    variant: av,
+                    value: a,
+                    ..
+                },
+                NewtypeVariant {
+                    name: bn,
+                    variant: bv,
+                    value: b,
+                    ..
+                },
+            ) => an == bn && av == bv && a.same_shape(b),
+            (Seq(a), Seq(b)) | (Tuple(a), Tuple(b)) => same_shape_elements(a, b),
+            (TupleStruct(an, a), TupleStruct(bn, b)) => an == bn && same_shape_elements(a, b),
+            (
+                TupleVariant {
+                    name: an,
+                    variant: av,
+                    fields: a,
+                    ..
+                },
+                TupleVariant {
+                    name: bn,
+                    variant: bv,
+                    fields: b,
+                    ..
+                },
+            ) => an == bn && av == bv && same_shape_elements(a, b),
+            (Map(a), Map(b)) => same_shape_entries(a, b),
+            (Struct(an, a), Struct(bn, b)) => an == bn && same_shape_entries(a, b),
+            (
+                StructVariant {
+                    name: an,
+                    variant: av,
+                    fields: a,
+                    ..
+                },
+                StructVariant {
+                    name: bn,
+                    variant: bv,
+                    fields: b,
+                    ..
+                },
+            ) => an == bn && av == bv && same_shape_entries(a, b),
+            _ => false,
+        }
+    }
+
+    fn is_number(&self) -> bool {
+        matches!(
+            self,
+            Value::I8(_)
+                | Value::I16(_)
+                | Value::I32(_)
+                | Value::I64(_)
+                | Value::I128(_)
+                | Value::U8(_)
+                | Value::U16(_)
+                | Value::U32(_)
+                | Value::U64(_)
+                | Value::U128(_)
+                | Value::F32(_)
+                | Value::F64(_)
+        )
+    }
+}
+
+fn same_shape_elements(a: &[Value], b: &[Value]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.same_shape(b))
+}
+
+fn same_shape_entries<K: Hash + Eq>(a: &IndexMap<K, Value>, b: &IndexMap<K, Value>) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .all(|(k, v)| b.get(k).is_some_and(|bv| v.same_shape(bv)))
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_same_shape() {
+        let template = Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Str("".to_string()),
+            Value::Str("port".to_string()) => Value::U16(0),
+            Value::Str("tags".to_string()) => Value::Seq(vec![Value::Str("".to_string())]),
+        });
+        let config = Value::Map(indexmap! {
+            Value::Str("tags".to_string()) => Value::Seq(vec![Value::Str("prod".to_string())]),
+            Value::Str("port".to_string()) => Value::I64(8080),
+            Value::Str("name".to_string()) => Value::Str("web".to_string()),
+        });
+        assert!(template.same_shape(&config));
+        assert!(config.same_shape(&template));
+    }
+
+    #[test]
+    fn test_same_shape_different_keys() {
+        let a = Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Str("a".to_string()),
+            Value::Str("port".to_string()) => Value::U16(0),
+        });
+        let b = Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Str("a".to_string()),
+            Value::Str("host".to_string()) => Value::U16(0),
+        });
+        assert!(!a.same_shape(&b));
+
+        assert!(!Value::Str("1".to_string()).same_shape(&Value::I32(1)));
+        assert!(!Value::Some(Box::new(Value::I32(1))).same_shape(&Value::None));
+    }
+}
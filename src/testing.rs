@@ -0,0 +1,133 @@
+use std::fmt::Debug;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::{from_value, from_value_with_config, into_value, Config};
+
+/// Assert that `value` bridges through [`Value`](crate::Value) without loss.
+///
+/// Checks the invariants this crate promises:
+///
+/// - `from_value(into_value(&value)?)? == value`, checked first.
+/// - The JSON of `value` equals the JSON of `into_value(&value)?`.
+/// - `value` survives `into_value` → JSON → `Value` →
+///   `from_value`. The last step uses
+///   [`Config::option_from_plain`](crate::Config::option_from_plain) since JSON
+///   has no options.
+///
+/// # Panics
+///
+/// Panics with the failed invariant if `value` doesn't round trip.
+///
+/// # Notes
+///
+/// Some types are not expected to pass and shouldn't use this:
+///
+/// - Floats containing `NaN`, which never equals itself.
+/// - Types whose `Serialize` and `Deserialize` disagree on shape, like custom
+///   `serialize_with` without a matching `deserialize_with` or fields with
+///   `#[serde(skip_deserializing)]`.
+///
+/// JSON can't keep everything `Value` does, so these also fail:
+///
+/// - `Some(None)` of `Option<Option<T>>`, which becomes `null`.
+/// - Maps with non-string keys, which JSON turns into strings.
+/// - `i128`/`u128` out of the range of `i64`/`u64`.
+///
+/// # Examples
+///
+/// ```
+/// use serde_bridge::assert_roundtrip;
+///
+/// assert_roundtrip(vec![Some(1u8), None]);
+/// ```
+pub fn assert_roundtrip<T>(value: T)
+where
+    T: Serialize + DeserializeOwned + PartialEq + Debug,
+{
+    let v = into_value(&value).unwrap_or_else(|e| panic!("into_value {:?}: {}", value, e));
+
+    let actual: T = from_value(v.clone()).unwrap_or_else(|e| panic!("from_value {:?}: {}", v, e));
+    assert_eq!(value, actual, "value changed after round trip");
+
+    let expected = serde_json::to_string(&value)
+        .unwrap_or_else(|e| panic!("serialize {:?} into json: {}", value, e));
+    let actual =
+        serde_json::to_string(&v).unwrap_or_else(|e| panic!("serialize {:?} into json: {}", v, e));
+    assert_eq!(expected, actual, "json of value and bridged value differ");
+
+    let from_json: crate::Value = serde_json::from_str(&actual)
+        .unwrap_or_else(|e| panic!("deserialize {} into value: {}", actual, e));
+    let actual: T =
+        from_value_with_config(from_json.clone(), Config::new().option_from_plain(true))
+            .unwrap_or_else(|e| panic!("from_value {:?}: {}", from_json, e));
+    assert_eq!(value, actual, "value changed after round trip through json");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::fixtures::test_struct;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Config {
+        name: String,
+        ports: Vec<u16>,
+        labels: BTreeMap<String, String>,
+        debug: Option<bool>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Event {
+        Start,
+        Stop(u32),
+        Move { x: i64, y: i64 },
+    }
+
+    #[test]
+    fn test_assert_roundtrip() {
+        assert_roundtrip(Config {
+            name: "web".to_string(),
+            ports: vec![80, 443],
+            labels: BTreeMap::from([("env".to_string(), "prod".to_string())]),
+            debug: None,
+        });
+        assert_roundtrip(vec![
+            Event::Start,
+            Event::Stop(1),
+            Event::Move { x: 1, y: -1 },
+        ]);
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Outer {
+        Event(Event),
+        Events(Vec<Event>),
+        Empty,
+    }
+
+    #[test]
+    fn test_assert_roundtrip_shapes() {
+        assert_roundtrip(test_struct());
+        assert_roundtrip((1u8, "a".to_string(), -2.5f32));
+        assert_roundtrip(vec![
+            Outer::Event(Event::Move { x: 1, y: 2 }),
+            Outer::Events(vec![Event::Start, Event::Stop(3)]),
+            Outer::Empty,
+        ]);
+        assert_roundtrip(Some(Event::Stop(1)));
+        assert_roundtrip(None::<Event>);
+        assert_roundtrip(vec![Some(1i64), None]);
+    }
+
+    #[test]
+    #[should_panic(expected = "value changed after round trip")]
+    fn test_assert_roundtrip_nan() {
+        assert_roundtrip(f64::NAN);
+    }
+}
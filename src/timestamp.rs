@@ -0,0 +1,157 @@
+use anyhow::anyhow;
+use chrono::{DateTime, Datelike, SecondsFormat};
+
+use crate::{Error, Value};
+
+/// The target representation of [`Value::normalize_timestamps`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// Seconds since the unix epoch as `Value::I64`.
+    EpochSeconds,
+    /// [RFC 3339](https://datatracker.ietf.org/doc/html/rfc3339) string in UTC as
+    /// `Value::Str`, like `2022-03-04T05:06:07Z`.
+    Rfc3339,
+}
+
+impl Value {
+    /// Convert timestamp nodes at the given JSON pointers into `to`.
+    ///
+    /// Supported inputs:
+    ///
+    /// - Integers of any width are seconds since the unix epoch.
+    /// - Strings in RFC 3339 format parsed by `chrono`, like `2022-03-04T05:06:07Z` or
+    ///   `2022-03-04T13:06:07.5+08:00`. `t`, `z` and a space separator are accepted.
+    ///   Fractional seconds are truncated.
+    ///
+    /// `Some` and newtype wrappers around a timestamp are kept. Paths that don't exist
+    /// are skipped so optional fields can be listed, but an existing node that isn't a
+    /// supported timestamp is an error. Nodes converted before the error are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{TimestampFormat, Value};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut v = Value::Seq(vec![Value::I64(0)]);
+    /// v.normalize_timestamps(&["/0"], TimestampFormat::Rfc3339)?;
+    /// assert_eq!(v, Value::Seq(vec![Value::Str("1970-01-01T00:00:00Z".to_string())]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn normalize_timestamps(
+        &mut self,
+        paths: &[&str],
+        to: TimestampFormat,
+    ) -> Result<(), Error> {
+        for path in paths {
+            let v = match self.pointer_mut(path) {
+                Some(v) => v.inner_mut(),
+                None => continue,
+            };
+
+            let secs = to_epoch_seconds(v)
+                .map_err(|e| Error(anyhow!("normalize timestamp at `{}`: {}", path, e)))?;
+            *v = match to {
+                TimestampFormat::EpochSeconds => Value::I64(secs),
+                TimestampFormat::Rfc3339 => Value::Str(
+                    format_rfc3339(secs)
+                        .map_err(|e| Error(anyhow!("normalize timestamp at `{}`: {}", path, e)))?,
+                ),
+            };
+        }
+
+        Ok(())
+    }
+}
+
+fn to_epoch_seconds(v: &Value) -> Result<i64, Error> {
+    let secs = match v {
+        Value::I8(v) => *v as i64,
+        Value::I16(v) => *v as i64,
+        Value::I32(v) => *v as i64,
+        Value::I64(v) => *v,
+        Value::I128(v) => i64::try_from(*v)?,
+        Value::U8(v) => *v as i64,
+        Value::U16(v) => *v as i64,
+        Value::U32(v) => *v as i64,
+        Value::U64(v) => i64::try_from(*v)?,
+        Value::U128(v) => i64::try_from(*v)?,
+        Value::Str(v) => DateTime::parse_from_rfc3339(v)
+            .map_err(|e| Error(anyhow!("invalid rfc3339 timestamp `{}`: {}", v, e)))?
+            .timestamp(),
+        v => return Err(Error(anyhow!("unsupported timestamp {:?}", v))),
+    };
+    Ok(secs)
+}
+
+/// Format seconds since the unix epoch as a RFC 3339 string in UTC.
+fn format_rfc3339(secs: i64) -> Result<String, Error> {
+    match DateTime::from_timestamp(secs, 0) {
+        Some(dt) if (0..=9999).contains(&dt.year()) => {
+            Ok(dt.to_rfc3339_opts(SecondsFormat::Secs, true))
+        }
+        _ => Err(Error(anyhow!(
+            "timestamp {} is out of range for rfc3339",
+            secs
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_epoch_to_rfc3339() -> Result<()> {
+        let mut v = Value::Map(indexmap! {
+            Value::Str("created".to_string()) => Value::I64(1646370367),
+            Value::Str("updated".to_string()) => Value::Some(Box::new(Value::U32(951782400))),
+        });
+        v.normalize_timestamps(
+            &["/created", "/updated", "/deleted"],
+            TimestampFormat::Rfc3339,
+        )?;
+
+        assert_eq!(
+            v.pointer("/created"),
+            Some(&Value::Str("2022-03-04T05:06:07Z".to_string()))
+        );
+        assert_eq!(
+            v.pointer("/updated"),
+            Some(&Value::Some(Box::new(Value::Str(
+                "2000-02-29T00:00:00Z".to_string()
+            ))))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rfc3339_to_epoch() -> Result<()> {
+        let mut v = Value::Seq(vec![
+            Value::Str("2022-03-04T05:06:07Z".to_string()),
+            Value::Str("2022-03-04T13:06:07.999+08:00".to_string()),
+            Value::Str("1969-12-31t23:59:59z".to_string()),
+            Value::Str("2022-03-04 05:06:07-00:00".to_string()),
+        ]);
+        v.normalize_timestamps(&["/0", "/1", "/2", "/3"], TimestampFormat::EpochSeconds)?;
+        assert_eq!(
+            v,
+            Value::Seq(vec![
+                Value::I64(1646370367),
+                Value::I64(1646370367),
+                Value::I64(-1),
+                Value::I64(1646370367),
+            ])
+        );
+
+        let mut v = Value::Seq(vec![Value::Str("2022-02-30T00:00:00Z".to_string())]);
+        assert!(v
+            .normalize_timestamps(&["/0"], TimestampFormat::EpochSeconds)
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Value};
+
+/// Transcode data from a [`serde::Deserializer`] into a [`serde::Serializer`] through [`Value`].
+///
+/// The whole input will be materialized as a [`Value`] first. To stream from one format
+/// to another without buffering, use `serde_transcode` directly with formats' own
+/// deserializer and serializer; our [`Deserializer`](crate::Deserializer) and
+/// [`Serializer`](crate::Serializer) work with it as well.
+///
+/// # Examples
+///
+/// ```
+/// use serde_bridge::transcode;
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let mut de = serde_json::Deserializer::from_str(r#"{"a":[1,2,3]}"#);
+/// let mut buf = Vec::new();
+/// transcode(&mut de, &mut serde_json::Serializer::new(&mut buf))?;
+/// assert_eq!(buf, br#"{"a":[1,2,3]}"#);
+/// # Ok(())
+/// # }
+/// ```
+pub fn transcode<'de, D, S>(d: D, s: S) -> Result<S::Ok, Error>
+where
+    D: serde::Deserializer<'de>,
+    S: serde::Serializer,
+{
+    let value = Value::deserialize(d).map_err(|e| Error(anyhow!("transcode: {}", e)))?;
+    value
+        .serialize(s)
+        .map_err(|e| Error(anyhow!("transcode: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{all_variants, ALL_VARIANTS_JSON};
+    use crate::Deserializer;
+
+    #[test]
+    fn test_transcode_all_variants() -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        transcode(
+            Deserializer::new(Value::Seq(all_variants())),
+            &mut serde_json::Serializer::new(&mut buf),
+        )?;
+        assert_eq!(String::from_utf8(buf)?, ALL_VARIANTS_JSON);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_into_json_value() -> anyhow::Result<()> {
+        let expected: serde_json::Value = serde_json::from_str(ALL_VARIANTS_JSON)?;
+
+        for (v, expected) in all_variants()
+            .into_iter()
+            .zip(expected.as_array().expect("must be array"))
+        {
+            // `serde_json::Value` doesn't accept bytes, serializers of serde_json
+            // emit them as arrays instead.
+            if let Value::Bytes(_) = v {
+                assert!(serde_json::Value::deserialize(Deserializer::new(v)).is_err());
+                continue;
+            }
+
+            let actual = serde_json::Value::deserialize(Deserializer::new(v.clone()))
+                .map_err(|e| anyhow!("deserialize {:?}: {}", v, e))?;
+            assert_eq!(&actual, expected, "{:?}", v);
+        }
+
+        Ok(())
+    }
+}
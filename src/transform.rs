@@ -0,0 +1,732 @@
+use std::mem;
+
+use anyhow::anyhow;
+use indexmap::IndexMap;
+
+use crate::{Error, Value};
+
+/// Render a map key into its string form.
+///
+/// Scalars use their natural display form, `None`/`Unit` become `null`, wrappers are
+/// unwrapped and unit variants use the variant name. Other keys fall back to `Debug`.
+pub(crate) fn key_to_string(key: &Value) -> String {
+    match key {
+        Value::Bool(v) => v.to_string(),
+        Value::I8(v) => v.to_string(),
+        Value::I16(v) => v.to_string(),
+        Value::I32(v) => v.to_string(),
+        Value::I64(v) => v.to_string(),
+        Value::I128(v) => v.to_string(),
+        Value::U8(v) => v.to_string(),
+        Value::U16(v) => v.to_string(),
+        Value::U32(v) => v.to_string(),
+        Value::U64(v) => v.to_string(),
+        Value::U128(v) => v.to_string(),
+        Value::F32(v) => v.to_string(),
+        Value::F64(v) => v.to_string(),
+        Value::Char(v) => v.to_string(),
+        Value::Str(v) => v.clone(),
+        Value::None | Value::Unit => "null".to_string(),
+        Value::Some(v) | Value::NewtypeStruct(_, v) => key_to_string(v),
+        Value::Shared(v) => key_to_string(v),
+        Value::UnitStruct(name) => name.to_string(),
+        Value::UnitVariant { variant, .. } => variant.to_string(),
+        v => format!("{:?}", v),
+    }
+}
+
+impl Value {
+    /// Convert every non-string map key in the whole tree into `Value::Str` in one walk.
+    ///
+    /// After this call, the value can be serialized into formats that only allow
+    /// string keys like JSON.
+    ///
+    /// # Notes
+    ///
+    /// This conversion is lossy: the original key type can't be recovered on the
+    /// return trip (`I32(1)` and `Str("1")` become the same key). If two keys collide
+    /// after conversion, the later value wins while keeping the position of the first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::indexmap;
+    /// use serde_bridge::Value;
+    ///
+    /// let mut v = Value::Map(indexmap! { Value::I32(1) => Value::Bool(true) });
+    /// v.stringify_all_keys();
+    /// assert_eq!(v, Value::Map(indexmap! { Value::Str("1".to_string()) => Value::Bool(true) }));
+    /// ```
+    pub fn stringify_all_keys(&mut self) {
+        if let Value::Map(m) = self {
+            let entries = std::mem::take(m);
+            let mut stringified = IndexMap::with_capacity(entries.len());
+            for (k, v) in entries {
+                let k = match k {
+                    Value::Str(k) => Value::Str(k),
+                    k => Value::Str(key_to_string(&k)),
+                };
+                stringified.insert(k, v);
+            }
+            *m = stringified;
+        }
+
+        self.for_each_child_mut(|v| v.stringify_all_keys());
+    }
+}
+
+/// Parse a string as a number following the rules of [`Value::parse_numeric_strings`].
+fn parse_numeric(s: &str) -> Option<Value> {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    // Empty strings, explicit `+` signs and leading zeros like zip codes are ambiguous.
+    if digits.is_empty()
+        || !digits.starts_with(|c: char| c.is_ascii_digit())
+        || (digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0."))
+    {
+        return None;
+    }
+
+    if let Ok(v) = s.parse::<i64>() {
+        return Some(Value::I64(v));
+    }
+    if let Ok(v) = s.parse::<u64>() {
+        return Some(Value::U64(v));
+    }
+    // Don't treat spellings like `inf` or `NaN` as numbers.
+    if s.chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+    {
+        if let Ok(v) = s.parse::<f64>() {
+            if v.is_finite() {
+                return Some(Value::F64(v));
+            }
+        }
+    }
+
+    None
+}
+
+impl Value {
+    /// Parse every `Value::Str` leaf in the tree that looks like a number.
+    ///
+    /// This normalizes values from string-only sources (CSV, env, query strings) so
+    /// that numeric fields can be deserialized cleanly.
+    ///
+    /// Parse order:
+    ///
+    /// 1. integer: `I64`, or `U64` if it's too large for `i64`.
+    /// 2. float: `F64`, only finite values written with digits, `.`, `e`/`E` and signs.
+    ///
+    /// Strings stay untouched if they are empty, start with `+`, have leading zeros
+    /// (like `"007"`), contain whitespace or spell special floats like `"inf"`/`"NaN"`.
+    /// Map keys are not changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let mut v = Value::Seq(vec![Value::Str("42".to_string()), Value::Str("x".to_string())]);
+    /// v.parse_numeric_strings();
+    /// assert_eq!(v, Value::Seq(vec![Value::I64(42), Value::Str("x".to_string())]));
+    /// ```
+    pub fn parse_numeric_strings(&mut self) {
+        self.visit_mut(&mut |v| {
+            if let Value::Str(s) = v {
+                if let Some(n) = parse_numeric(s) {
+                    *v = n;
+                }
+            }
+        });
+    }
+}
+
+impl Value {
+    /// Replace every node deeper than `max_depth` with `placeholder`.
+    ///
+    /// The root is at depth `0` and every child is one level deeper than its parent,
+    /// including the inner value of `Some`, newtype structs and newtype variants.
+    /// This is useful to keep debug dumps of deeply nested values bounded. Unlike the
+    /// depth limit of deserializing, this never fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let mut v = Value::Seq(vec![Value::Seq(vec![Value::I32(1)])]);
+    /// v.truncate_depth(1, Value::Str("…".to_string()));
+    /// assert_eq!(v, Value::Seq(vec![Value::Seq(vec![Value::Str("…".to_string())])]));
+    /// ```
+    pub fn truncate_depth(&mut self, max_depth: usize, placeholder: Value) {
+        if max_depth == 0 {
+            self.for_each_child_mut(|v| *v = placeholder.clone());
+        } else {
+            self.for_each_child_mut(|v| v.truncate_depth(max_depth - 1, placeholder.clone()));
+        }
+    }
+}
+
+/// Normalize an identifier by dropping case and separators.
+fn normalize_ident(s: &str) -> String {
+    s.chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' ' | '.'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl Value {
+    /// Rename keys of a top level `Map` that loosely match `fields` to the canonical names.
+    ///
+    /// Keys are matched after dropping case and separators (`_`, `-`, ` ` and `.`), so
+    /// `firstName`, `FirstName`, `first-name` and `FIRST_NAME` all match `first_name`.
+    /// This makes a following `from_value` into a struct with these fields succeed.
+    ///
+    /// - Keys that already equal a field are kept.
+    /// - A key is not renamed if the canonical name is already present, or if several
+    ///   fields share the same normalized form.
+    /// - Non-string keys and nested values are left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::indexmap;
+    /// use serde_bridge::Value;
+    ///
+    /// let mut v = Value::Map(indexmap! { Value::Str("firstName".to_string()) => Value::Bool(true) });
+    /// v.coerce_for_struct(&["first_name"]);
+    /// assert_eq!(v, Value::Map(indexmap! { Value::Str("first_name".to_string()) => Value::Bool(true) }));
+    /// ```
+    pub fn coerce_for_struct(&mut self, fields: &[&str]) {
+        let m = match self.inner_mut() {
+            Value::Map(m) => m,
+            _ => return,
+        };
+
+        let mut renames = IndexMap::new();
+        for k in m.keys() {
+            let k = match k {
+                Value::Str(k) if !fields.contains(&k.as_str()) => k,
+                _ => continue,
+            };
+            let normalized = normalize_ident(k);
+            let mut matched = fields.iter().filter(|f| normalize_ident(f) == normalized);
+            if let (Some(field), None) = (matched.next(), matched.next()) {
+                let key = Value::Str(field.to_string());
+                // Keep the first key if several keys match the same field.
+                if !m.contains_key(&key) && !renames.values().any(|v| v == &key) {
+                    renames.insert(k.clone(), key);
+                }
+            }
+        }
+        if renames.is_empty() {
+            return;
+        }
+
+        *m = std::mem::take(m)
+            .into_iter()
+            .map(|(k, v)| match &k {
+                Value::Str(s) => (renames.swap_remove(s).unwrap_or(k), v),
+                _ => (k, v),
+            })
+            .collect();
+    }
+}
+
+impl Value {
+    /// Rewrite `variant_index` of enum `name` in the whole tree by looking up variant names.
+    ///
+    /// Values persisted before an enum gained new variants may carry stale indexes
+    /// that no longer match their variant names. For every unit, newtype, tuple and
+    /// struct variant of enum `name`, the index is set to the position of its
+    /// `variant` in `variants`. Variants that don't exist in `variants` are left
+    /// untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let mut v = Value::UnitVariant { name: "E", variant_index: 1, variant: "B" };
+    /// v.fix_variant_indices("E", &["A", "New", "B"]);
+    /// assert_eq!(v, Value::UnitVariant { name: "E", variant_index: 2, variant: "B" });
+    /// ```
+    pub fn fix_variant_indices(&mut self, name: &str, variants: &[&'static str]) {
+        self.visit_mut(&mut |v| {
+            let (vn, vi, vv) = match v {
+                Value::UnitVariant {
+                    name,
+                    variant_index,
+                    variant,
+                }
+                | Value::NewtypeVariant {
+                    name,
+                    variant_index,
+                    variant,
+                    ..
+                }
+                | Value::TupleVariant {
+                    name,
+                    variant_index,
+                    variant,
+                    ..
+                }
+                | Value::StructVariant {
+                    name,
+                    variant_index,
+                    variant,
+                    ..
+                } => (*name, variant_index, *variant),
+                _ => return,
+            };
+            if vn != name {
+                return;
+            }
+            if let Some(idx) = variants.iter().position(|v| *v == vv) {
+                *vi = idx as u32;
+            }
+        });
+    }
+}
+
+/// The canonical byte representation picked by [`Value::normalize_bytes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BytePolicy {
+    /// Collapse non-empty `Value::Seq` of `Value::U8` into `Value::Bytes`.
+    PreferBytes,
+    /// Expand `Value::Bytes` into `Value::Seq` of `Value::U8`.
+    PreferSeq,
+}
+
+impl Value {
+    /// Use one representation for bytes in the whole tree.
+    ///
+    /// `Vec<u8>` serializes as a seq of `U8` while `serde_bytes` and some formats
+    /// produce `Value::Bytes`. This converts between them following `policy`.
+    ///
+    /// Empty seqs are never collapsed into bytes since they can't be told apart
+    /// from empty seqs of other types.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{BytePolicy, Value};
+    ///
+    /// let mut v = Value::Seq(vec![Value::U8(1), Value::U8(2)]);
+    /// v.normalize_bytes(BytePolicy::PreferBytes);
+    /// assert_eq!(v, Value::Bytes(vec![1, 2]));
+    /// ```
+    pub fn normalize_bytes(&mut self, policy: BytePolicy) {
+        self.visit_mut(&mut |v| match (policy, &*v) {
+            (BytePolicy::PreferBytes, Value::Seq(elements)) if !elements.is_empty() => {
+                let bytes: Option<Vec<u8>> = elements
+                    .iter()
+                    .map(|v| match v {
+                        Value::U8(b) => Some(*b),
+                        _ => None,
+                    })
+                    .collect();
+                if let Some(bytes) = bytes {
+                    *v = Value::Bytes(bytes);
+                }
+            }
+            (BytePolicy::PreferSeq, Value::Bytes(bytes)) => {
+                *v = Value::Seq(bytes.iter().copied().map(Value::U8).collect());
+            }
+            _ => {}
+        });
+    }
+}
+
+impl Value {
+    /// Build a `Value::Map` with entries sorted by key.
+    ///
+    /// `Value::Map` keeps insertion order, which round-trips formats like JSON as
+    /// is. Sorted maps give a canonical order instead, like `BTreeMap`, at the cost
+    /// of sorting on build. Keys are ordered by the `Ord` of [`Value`] and the later
+    /// value wins for duplicated keys.
+    ///
+    /// Map equality doesn't depend on the order of entries, so sorted and unsorted
+    /// maps with the same entries are equal.
+    ///
+    /// Entries are only sorted on build: [`Value::entry`] and `IndexMap::insert`
+    /// append new keys at the end. Use [`Value::insert_sorted`] to keep the order,
+    /// or [`Value::sort_keys`] to restore it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let v = Value::sorted_map(vec![
+    ///     (Value::Str("b".to_string()), Value::I32(2)),
+    ///     (Value::Str("a".to_string()), Value::I32(1)),
+    /// ]);
+    /// assert_eq!(v.to_pretty_string(0), "{\n\"a\": 1,\n\"b\": 2\n}");
+    /// ```
+    pub fn sorted_map(entries: impl IntoIterator<Item = (Value, Value)>) -> Value {
+        let mut m: IndexMap<Value, Value> = entries.into_iter().collect();
+        m.sort_keys();
+        Value::Map(m)
+    }
+
+    /// Insert an entry into a sorted `Value::Map`, keeping it sorted by key.
+    ///
+    /// New keys are placed at their position by binary search, existing keys keep
+    /// their position and have their value replaced, which is returned. The map is
+    /// expected to be sorted already, for example built by [`Value::sorted_map`].
+    ///
+    /// Returns an error if value is not a `Map`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut v = Value::sorted_map(vec![
+    ///     (Value::Str("c".to_string()), Value::I32(3)),
+    ///     (Value::Str("a".to_string()), Value::I32(1)),
+    /// ]);
+    /// v.insert_sorted("b", Value::I32(2))?;
+    /// assert_eq!(v.to_pretty_string(0), "{\n\"a\": 1,\n\"b\": 2,\n\"c\": 3\n}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert_sorted(
+        &mut self,
+        key: impl Into<Value>,
+        value: impl Into<Value>,
+    ) -> Result<Option<Value>, Error> {
+        let m = match self {
+            Value::Map(m) => m,
+            v => return Err(Error(anyhow!("invalid type: {}, expect map", v.kind()))),
+        };
+        let (key, value) = (key.into(), value.into());
+        if let Some(old) = m.get_mut(&key) {
+            return Ok(Some(mem::replace(old, value)));
+        }
+
+        let (mut lo, mut hi) = (0, m.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if m.get_index(mid).expect("index must be valid").0 < &key {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        let tail = m.split_off(lo);
+        m.insert(key, value);
+        m.extend(tail);
+        Ok(None)
+    }
+
+    /// Sort entries of every `Value::Map` in the tree by key.
+    ///
+    /// Struct fields keep their declaration order since positional formats depend
+    /// on it.
+    pub fn sort_keys(&mut self) {
+        self.visit_mut(&mut |v| {
+            if let Value::Map(m) = v {
+                m.sort_keys();
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_stringify_all_keys() -> Result<()> {
+        let mut v = Value::Struct(
+            "TestStruct",
+            indexmap! {
+                "a" => Value::Map(indexmap! {
+                    Value::I32(1) => Value::Seq(vec![Value::Map(indexmap! {
+                        Value::Bool(true) => Value::U8(1),
+                    })]),
+                    Value::Str("b".to_string()) => Value::Map(indexmap! {
+                        Value::U64(2) => Value::U8(2),
+                    }),
+                }),
+            },
+        );
+        v.stringify_all_keys();
+
+        assert_eq!(
+            serde_json::to_string(&v)?,
+            r#"{"a":{"1":[{"true":1}],"b":{"2":2}}}"#
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_numeric_strings() {
+        let mut v = Value::Map(indexmap! {
+            Value::Str("a".to_string()) => Value::Str("42".to_string()),
+            Value::Str("b".to_string()) => Value::Str("2.5".to_string()),
+            Value::Str("c".to_string()) => Value::Str("hello".to_string()),
+            Value::Str("d".to_string()) => Value::Seq(vec![
+                Value::Str("-1".to_string()),
+                Value::Str("18446744073709551615".to_string()),
+                Value::Str("1e3".to_string()),
+            ]),
+            Value::Str("e".to_string()) => Value::Seq(vec![
+                Value::Str("".to_string()),
+                Value::Str("007".to_string()),
+                Value::Str("+1".to_string()),
+                Value::Str("inf".to_string()),
+                Value::Str(" 1".to_string()),
+            ]),
+        });
+        v.parse_numeric_strings();
+
+        assert_eq!(
+            v,
+            Value::Map(indexmap! {
+                Value::Str("a".to_string()) => Value::I64(42),
+                Value::Str("b".to_string()) => Value::F64(2.5),
+                Value::Str("c".to_string()) => Value::Str("hello".to_string()),
+                Value::Str("d".to_string()) => Value::Seq(vec![
+                    Value::I64(-1),
+                    Value::U64(u64::MAX),
+                    Value::F64(1000.0),
+                ]),
+                Value::Str("e".to_string()) => Value::Seq(vec![
+                    Value::Str("".to_string()),
+                    Value::Str("007".to_string()),
+                    Value::Str("+1".to_string()),
+                    Value::Str("inf".to_string()),
+                    Value::Str(" 1".to_string()),
+                ]),
+            })
+        );
+    }
+
+    #[test]
+    fn test_truncate_depth() {
+        fn nested(level: usize) -> Value {
+            if level == 0 {
+                Value::I32(0)
+            } else {
+                Value::Map(indexmap! {
+                    Value::Str(format!("l{}", level)) => nested(level - 1),
+                })
+            }
+        }
+
+        let mut v = nested(5);
+        v.truncate_depth(2, Value::Str("…".to_string()));
+        assert_eq!(
+            v,
+            Value::Map(indexmap! {
+                Value::Str("l5".to_string()) => Value::Map(indexmap! {
+                    Value::Str("l4".to_string()) => Value::Map(indexmap! {
+                        Value::Str("l3".to_string()) => Value::Str("…".to_string()),
+                    }),
+                }),
+            })
+        );
+
+        let mut v = nested(1);
+        v.truncate_depth(2, Value::Str("…".to_string()));
+        assert_eq!(v, nested(1));
+    }
+
+    #[test]
+    fn test_coerce_for_struct() -> anyhow::Result<()> {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Person {
+            first_name: String,
+            last_name: String,
+            age: u8,
+        }
+
+        let mut v = Value::Map(indexmap! {
+            Value::Str("firstName".to_string()) => Value::Str("Ada".to_string()),
+            Value::Str("LAST_NAME".to_string()) => Value::Str("Lovelace".to_string()),
+            Value::Str("age".to_string()) => Value::U8(36),
+            Value::Str("Age".to_string()) => Value::U8(0),
+        });
+        v.coerce_for_struct(&["first_name", "last_name", "age"]);
+
+        let keys: Vec<_> = match &v {
+            Value::Map(m) => m.keys().map(key_to_string).collect(),
+            _ => unreachable!(),
+        };
+        assert_eq!(keys, vec!["first_name", "last_name", "age", "Age"]);
+
+        let p: Person = crate::from_value(v)?;
+        assert_eq!(
+            p,
+            Person {
+                first_name: "Ada".to_string(),
+                last_name: "Lovelace".to_string(),
+                age: 36,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_fix_variant_indices() -> anyhow::Result<()> {
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        enum Shape {
+            Point,
+            Line(u8),
+            Circle { r: u8 },
+        }
+
+        // Persisted when the enum was `enum Shape { Point, Circle { r: u8 } }`.
+        let mut v = Value::Seq(vec![
+            Value::UnitVariant {
+                name: "Shape",
+                variant_index: 0,
+                variant: "Point",
+            },
+            Value::StructVariant {
+                name: "Shape",
+                variant_index: 1,
+                variant: "Circle",
+                fields: indexmap! { "r" => Value::U8(3) },
+            },
+        ]);
+        assert!(crate::from_value::<Vec<Shape>>(v.clone()).is_err());
+
+        v.fix_variant_indices("Shape", &["Point", "Line", "Circle"]);
+        assert_eq!(
+            crate::from_value::<Vec<Shape>>(v)?,
+            vec![Shape::Point, Shape::Circle { r: 3 }]
+        );
+
+        // Persisted when the enum had more variants than it has now.
+        let mut v = Value::UnitVariant {
+            name: "Shape",
+            variant_index: 7,
+            variant: "Point",
+        };
+        let err = crate::from_value::<Shape>(v.clone()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "variant `Point` of enum `Shape` has stale index 7, use `Value::fix_variant_indices` to update it"
+        );
+
+        v.fix_variant_indices("Shape", &["Point", "Line", "Circle"]);
+        assert_eq!(crate::from_value::<Shape>(v)?, Shape::Point);
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_bytes() {
+        let mixed = Value::Map(indexmap! {
+            Value::Str("a".to_string()) => Value::Bytes(vec![1, 2]),
+            Value::Str("b".to_string()) => Value::Some(Box::new(Value::Seq(vec![Value::U8(3)]))),
+            Value::Str("c".to_string()) => Value::Seq(vec![Value::U8(4), Value::U16(5)]),
+            Value::Str("d".to_string()) => Value::Seq(vec![]),
+        });
+
+        let mut v = mixed.clone();
+        v.normalize_bytes(BytePolicy::PreferBytes);
+        assert_eq!(
+            v,
+            Value::Map(indexmap! {
+                Value::Str("a".to_string()) => Value::Bytes(vec![1, 2]),
+                Value::Str("b".to_string()) => Value::Some(Box::new(Value::Bytes(vec![3]))),
+                Value::Str("c".to_string()) => Value::Seq(vec![Value::U8(4), Value::U16(5)]),
+                Value::Str("d".to_string()) => Value::Seq(vec![]),
+            })
+        );
+
+        let mut v = mixed;
+        v.normalize_bytes(BytePolicy::PreferSeq);
+        assert_eq!(
+            v,
+            Value::Map(indexmap! {
+                Value::Str("a".to_string()) => Value::Seq(vec![Value::U8(1), Value::U8(2)]),
+                Value::Str("b".to_string()) => Value::Some(Box::new(Value::Seq(vec![Value::U8(3)]))),
+                Value::Str("c".to_string()) => Value::Seq(vec![Value::U8(4), Value::U16(5)]),
+                Value::Str("d".to_string()) => Value::Seq(vec![]),
+            })
+        );
+    }
+
+    #[test]
+    fn test_sort_keys() {
+        let mut v = Value::Map(indexmap! {
+            Value::Str("b".to_string()) => Value::Map(indexmap! {
+                Value::I64(2) => Value::Unit,
+                Value::U8(1) => Value::Unit,
+            }),
+            Value::Str("a".to_string()) => Value::Struct("S", indexmap! {
+                "y" => Value::Unit,
+                "x" => Value::Unit,
+            }),
+        });
+        let original = v.clone();
+        v.sort_keys();
+
+        assert_eq!(v, original);
+        assert_eq!(
+            v.flatten_to_map(".").keys().collect::<Vec<_>>(),
+            vec!["a.y", "a.x", "b.1", "b.2"]
+        );
+
+        let sorted = Value::sorted_map(vec![
+            (Value::I32(3), Value::Unit),
+            (Value::I32(1), Value::Unit),
+            (Value::I32(3), Value::Bool(true)),
+        ]);
+        assert_eq!(
+            sorted
+                .into_map()
+                .expect("must be map")
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![
+                (Value::I32(1), Value::Unit),
+                (Value::I32(3), Value::Bool(true))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_insert_sorted() -> Result<()> {
+        let mut v = Value::sorted_map(vec![
+            (Value::I32(3), Value::Unit),
+            (Value::I32(1), Value::Unit),
+        ]);
+        assert_eq!(v.insert_sorted(Value::I32(2), Value::Unit)?, None);
+        assert_eq!(v.insert_sorted(Value::I32(0), Value::Unit)?, None);
+        assert_eq!(v.insert_sorted(Value::I32(4), Value::Unit)?, None);
+        assert_eq!(
+            v.insert_sorted(Value::I32(3), Value::Bool(true))?,
+            Some(Value::Unit)
+        );
+        let keys = |v: &Value| {
+            v.as_map()
+                .expect("must be map")
+                .keys()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(keys(&v), (0..5).map(Value::I32).collect::<Vec<_>>());
+
+        // Plain inserts append, `sort_keys` restores the order.
+        v.entry(Value::I32(-1))?.or_insert(Value::Unit);
+        assert_eq!(keys(&v).last(), Some(&Value::I32(-1)));
+        v.sort_keys();
+        assert_eq!(keys(&v), (-1..5).map(Value::I32).collect::<Vec<_>>());
+
+        assert!(Value::Unit
+            .insert_sorted(Value::I32(1), Value::Unit)
+            .is_err());
+        Ok(())
+    }
+}
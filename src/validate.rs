@@ -0,0 +1,280 @@
+use anyhow::anyhow;
+
+use crate::path::escape_token;
+use crate::transform::key_to_string;
+use crate::{Error, Value};
+
+impl Value {
+    /// Check that this object only contains keys from `allowed`.
+    ///
+    /// `Map` keys are compared in their string form, `Struct` and `StructVariant` by
+    /// field names. `Some` and newtype wrappers are transparent. Only the top level is
+    /// checked, use [`Value::assert_only_keys_by_path`] for nested objects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{into_value, Value};
+    /// # use anyhow::Result;
+    /// # use std::collections::BTreeMap;
+    /// # fn main() -> Result<()> {
+    /// let v = into_value(BTreeMap::from([("name", 1), ("nmae", 2)]))?;
+    /// let err = v.assert_only_keys(&["name"]).expect_err("must fail");
+    /// assert_eq!(err.to_string(), "unexpected keys: `nmae`");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn assert_only_keys(&self, allowed: &[&str]) -> Result<(), Error> {
+        let unexpected: Vec<String> = match self.inner() {
+            Value::Map(m) => m
+                .keys()
+                .map(key_to_string)
+                .filter(|k| !allowed.contains(&k.as_str()))
+                .collect(),
+            Value::Struct(_, fields) | Value::StructVariant { fields, .. } => fields
+                .keys()
+                .filter(|k| !allowed.contains(k))
+                .map(|k| k.to_string())
+                .collect(),
+            v => return Err(Error(anyhow!("expected an object, but got {:?}", v))),
+        };
+
+        if unexpected.is_empty() {
+            return Ok(());
+        }
+        Err(Error(anyhow!(
+            "unexpected keys: {}",
+            unexpected
+                .iter()
+                .map(|k| format!("`{}`", k))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )))
+    }
+
+    /// Check objects at multiple JSON pointers against their own allow-lists.
+    ///
+    /// Every `(pointer, allowed)` rule is checked via [`Value::assert_only_keys`].
+    /// Rules whose pointer doesn't exist are skipped so optional sections can be
+    /// listed. The first failing rule returns an error carrying its pointer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut v = Value::None;
+    /// v.set_path("/log/level", Value::Str("info".to_string()))?;
+    /// v.assert_only_keys_by_path(&[("", &["log"]), ("/log", &["level", "file"])])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn assert_only_keys_by_path(&self, rules: &[(&str, &[&str])]) -> Result<(), Error> {
+        for (pointer, allowed) in rules {
+            if let Some(v) = self.pointer(pointer) {
+                v.assert_only_keys(allowed)
+                    .map_err(|e| Error(anyhow!("check keys at `{}`: {}", pointer, e)))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Value {
+    /// Find the first node that strict JSON can't represent.
+    ///
+    /// Returns the JSON pointer of the node and the reason, or `None` if the value
+    /// can be serialized into JSON safely. Equivalent to
+    /// [`Value::first_json_unsafe_with`] with `check_safe_integers` disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let v = Value::Seq(vec![Value::F64(1.0), Value::F64(f64::NAN)]);
+    /// assert_eq!(v.first_json_unsafe(), Some(("/1".to_string(), "non-finite float")));
+    /// ```
+    pub fn first_json_unsafe(&self) -> Option<(String, &'static str)> {
+        self.first_json_unsafe_with(false)
+    }
+
+    /// Find the first node that strict JSON can't represent.
+    ///
+    /// Nodes are checked in depth-first pre-order, entries of maps are checked key
+    /// first. Every node is checked for, in this order:
+    ///
+    /// - `"non-finite float"`: `NaN` or infinite `F32`/`F64`.
+    /// - `"128-bit integer"`: `I128`/`U128`, even if the value is small.
+    /// - `"integer exceeds 2^53"`: integers outside `[-(2^53 - 1), 2^53 - 1]`, only
+    ///   if `check_safe_integers` is enabled. JavaScript can't represent them exactly.
+    /// - `"bytes"`: `Bytes` have no JSON representation.
+    /// - `"non-string map key"`: keys of `Map` other than `Str`, reported at the
+    ///   path of the entry.
+    pub fn first_json_unsafe_with(
+        &self,
+        check_safe_integers: bool,
+    ) -> Option<(String, &'static str)> {
+        let mut path = String::new();
+        self.find_json_unsafe(check_safe_integers, &mut path)
+            .map(|reason| (path, reason))
+    }
+
+    /// Leave `path` pointing at the unsafe node if found.
+    fn find_json_unsafe(
+        &self,
+        check_safe_integers: bool,
+        path: &mut String,
+    ) -> Option<&'static str> {
+        const MAX_SAFE_INTEGER: i128 = (1 << 53) - 1;
+
+        match self {
+            Value::F32(v) if !v.is_finite() => return Some("non-finite float"),
+            Value::F64(v) if !v.is_finite() => return Some("non-finite float"),
+            Value::I128(_) | Value::U128(_) => return Some("128-bit integer"),
+            Value::Bytes(_) => return Some("bytes"),
+            _ => {}
+        }
+        if check_safe_integers {
+            let i = match self {
+                Value::I64(v) => Some(*v as i128),
+                Value::U64(v) => Some(*v as i128),
+                _ => None,
+            };
+            if i.is_some_and(|i| !(-MAX_SAFE_INTEGER..=MAX_SAFE_INTEGER).contains(&i)) {
+                return Some("integer exceeds 2^53");
+            }
+        }
+
+        let len = path.len();
+        let child = |path: &mut String, token: &str, v: &Value| {
+            push_token(path, len, token);
+            v.find_json_unsafe(check_safe_integers, path)
+        };
+        let found = match self {
+            Value::Some(v) | Value::NewtypeStruct(_, v) => {
+                v.find_json_unsafe(check_safe_integers, path)
+            }
+            Value::NewtypeVariant { value, .. } => {
+                value.find_json_unsafe(check_safe_integers, path)
+            }
+            Value::Shared(v) => v.find_json_unsafe(check_safe_integers, path),
+            Value::Seq(v)
+            | Value::Tuple(v)
+            | Value::TupleStruct(_, v)
+            | Value::TupleVariant { fields: v, .. } => v
+                .iter()
+                .enumerate()
+                .find_map(|(idx, v)| child(path, &idx.to_string(), v)),
+            Value::Map(m) => m.iter().find_map(|(k, v)| {
+                if !matches!(k, Value::Str(_)) {
+                    push_token(path, len, &key_to_string(k));
+                    return Some("non-string map key");
+                }
+                child(path, &key_to_string(k), v)
+            }),
+            Value::Struct(_, fields) | Value::StructVariant { fields, .. } => {
+                fields.iter().find_map(|(k, v)| child(path, k, v))
+            }
+            _ => None,
+        };
+        if found.is_none() {
+            path.truncate(len);
+        }
+        found
+    }
+}
+
+/// Replace the last token of `path` after `len` with `token`.
+fn push_token(path: &mut String, len: usize, token: &str) {
+    path.truncate(len);
+    path.push('/');
+    path.push_str(&escape_token(token));
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_assert_only_keys() {
+        let v = Value::Map(indexmap! {
+            Value::Str("name".to_string()) => Value::Str("web".to_string()),
+            Value::Str("port".to_string()) => Value::U16(80),
+        });
+        assert!(v.assert_only_keys(&["name", "port", "host"]).is_ok());
+
+        let err = v.assert_only_keys(&["host"]).expect_err("must fail");
+        assert_eq!(err.to_string(), "unexpected keys: `name`, `port`");
+
+        assert!(Value::I32(1).assert_only_keys(&[]).is_err());
+    }
+
+    #[test]
+    fn test_assert_only_keys_by_path() {
+        let v = Value::Struct(
+            "Config",
+            indexmap! {
+                "server" => Value::Map(indexmap! {
+                    Value::Str("port".to_string()) => Value::U16(80),
+                    Value::Str("prot".to_string()) => Value::U16(81),
+                }),
+            },
+        );
+        let rules: &[(&str, &[&str])] = &[("", &["server"]), ("/server", &["port"]), ("/log", &[])];
+        let err = v.assert_only_keys_by_path(rules).expect_err("must fail");
+        assert_eq!(
+            err.to_string(),
+            "check keys at `/server`: unexpected keys: `prot`"
+        );
+    }
+
+    #[test]
+    fn test_first_json_unsafe() {
+        let v = Value::Struct(
+            "Config",
+            indexmap! {
+                "name" => Value::Str("web".to_string()),
+                "limits" => Value::Map(indexmap! {
+                    Value::Str("a/b".to_string()) => Value::Some(Box::new(Value::F64(f64::INFINITY))),
+                    Value::I32(1) => Value::Str("x".to_string()),
+                }),
+                "id" => Value::U128(1),
+                "raw" => Value::Bytes(vec![1]),
+                "big" => Value::U64(1 << 53),
+            },
+        );
+        assert_eq!(
+            v.first_json_unsafe(),
+            Some(("/limits/a~1b".to_string(), "non-finite float"))
+        );
+
+        let mut v = v;
+        v.pointer_mut("/limits/a~1b").unwrap().take();
+        assert_eq!(
+            v.first_json_unsafe(),
+            Some(("/limits/1".to_string(), "non-string map key"))
+        );
+
+        let v = Value::Seq(vec![
+            Value::TupleVariant {
+                name: "E",
+                variant_index: 0,
+                variant: "T",
+                fields: vec![Value::I64(-(1 << 53)), Value::U8(1)],
+            },
+            Value::Bytes(vec![]),
+        ]);
+        assert_eq!(v.first_json_unsafe(), Some(("/1".to_string(), "bytes")));
+        assert_eq!(
+            v.first_json_unsafe_with(true),
+            Some(("/0/0".to_string(), "integer exceeds 2^53"))
+        );
+
+        assert_eq!(Value::Str("ok".to_string()).first_json_unsafe(), None);
+    }
+}
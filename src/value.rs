@@ -1,7 +1,13 @@
+use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
+use anyhow::anyhow;
+use indexmap::map::Entry;
 use indexmap::IndexMap;
 
+use crate::Error;
+
 /// Value is the internal represents of serde's data format.
 ///
 /// Value is the one-to-one map to [serde's data format](https://serde.rs/data-model.html).
@@ -118,6 +124,11 @@ pub enum Value {
     /// It represents a named value containing no data.
     UnitStruct(&'static str),
     /// For example the `E::A` and `E::B` in `enum E { A, B }`.
+    ///
+    /// # Note
+    ///
+    /// Enums that serialize as integers (like `serde_repr`) don't call
+    /// `serialize_unit_variant`, so they are plain numbers like `Value::U8` instead.
     UnitVariant {
         name: &'static str,
         variant_index: u32,
@@ -166,6 +177,384 @@ pub enum Value {
         variant: &'static str,
         fields: IndexMap<&'static str, Value>,
     },
+    /// A reference-counted value created by [`Value::shared`].
+    ///
+    /// Cloning it is cheap since the inner value is shared instead of copied.
+    /// It is serialized and deserialized as its inner value.
+    ///
+    /// # Note
+    ///
+    /// Like other variants, `Shared(v)` doesn't equal to `v` itself, compare them
+    /// with [`Value::structural_eq`] instead.
+    Shared(Arc<Value>),
+}
+
+impl Value {
+    /// Take the value out, leaving `Value::default()` (`Value::None`) in its place.
+    ///
+    /// This is the `Value` version of `Option::take`, useful to move out of a
+    /// `&mut Value` during in-place transforms without cloning.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let mut v = Value::Bool(true);
+    /// assert_eq!(v.take(), Value::Bool(true));
+    /// assert_eq!(v, Value::None);
+    /// ```
+    pub fn take(&mut self) -> Value {
+        std::mem::take(self)
+    }
+
+    /// Consume value into its elements if it's a `Seq` or `Tuple`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let v = Value::Seq(vec![Value::Bool(true)]);
+    /// assert_eq!(v.into_seq(), Some(vec![Value::Bool(true)]));
+    /// assert_eq!(Value::Bool(true).into_seq(), None);
+    /// ```
+    pub fn into_seq(self) -> Option<Vec<Value>> {
+        match self {
+            Value::Seq(v) | Value::Tuple(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Consume value into its entries if it's a `Map`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::indexmap;
+    /// use serde_bridge::Value;
+    ///
+    /// let v = Value::Map(indexmap! { Value::I32(1) => Value::Bool(true) });
+    /// assert_eq!(v.into_map(), Some(indexmap! { Value::I32(1) => Value::Bool(true) }));
+    /// ```
+    pub fn into_map(self) -> Option<IndexMap<Value, Value>> {
+        match self {
+            Value::Map(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    /// Build a `Value::Seq` from given elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let v = Value::seq([Value::I32(1), Value::I32(2)]);
+    /// assert_eq!(v, Value::Seq(vec![Value::I32(1), Value::I32(2)]));
+    /// ```
+    pub fn seq(iter: impl IntoIterator<Item = impl Into<Value>>) -> Value {
+        Value::Seq(iter.into_iter().map(Into::into).collect())
+    }
+
+    /// Build a `Value::UnitVariant` of enum `name`, looking up `variant_index` by the
+    /// position of `variant` in `variants`.
+    ///
+    /// `variants` are all variant names of the enum in declaration order, the same
+    /// list serde passes to `deserialize_enum`. Returns an error if `variant` is not
+    /// in `variants`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// const VARIANTS: &[&str] = &["Red", "Green", "Blue"];
+    ///
+    /// let v = Value::unit_variant("Color", VARIANTS, "Green")?;
+    /// assert_eq!(
+    ///     v,
+    ///     Value::UnitVariant {
+    ///         name: "Color",
+    ///         variant_index: 1,
+    ///         variant: "Green",
+    ///     }
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unit_variant(
+        name: &'static str,
+        variants: &'static [&'static str],
+        variant: &str,
+    ) -> Result<Value, Error> {
+        let (variant_index, variant) = variants
+            .iter()
+            .enumerate()
+            .find(|(_, v)| **v == variant)
+            .ok_or_else(|| {
+                Error(anyhow!(
+                    "unknown variant `{}` of enum {}, expect one of {:?}",
+                    variant,
+                    name,
+                    variants
+                ))
+            })?;
+        Ok(Value::UnitVariant {
+            name,
+            variant_index: u32::try_from(variant_index)?,
+            variant,
+        })
+    }
+
+    /// Append an element to a `Seq` or `Tuple`.
+    ///
+    /// Returns an error if value is not a `Seq` or `Tuple`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut v = Value::Seq(vec![]);
+    /// v.push(Value::Bool(true))?;
+    /// assert_eq!(v, Value::Seq(vec![Value::Bool(true)]));
+    /// assert!(Value::Unit.push(Value::Bool(true)).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn push(&mut self, v: impl Into<Value>) -> Result<(), Error> {
+        self.seq_mut()?.push(v.into());
+        Ok(())
+    }
+
+    /// Append all elements of `iter` to a `Seq` or `Tuple`.
+    ///
+    /// Returns an error if value is not a `Seq` or `Tuple`, in which case
+    /// `iter` is not consumed.
+    pub fn extend(
+        &mut self,
+        iter: impl IntoIterator<Item = impl Into<Value>>,
+    ) -> Result<(), Error> {
+        self.seq_mut()?.extend(iter.into_iter().map(Into::into));
+        Ok(())
+    }
+
+    /// Get the entry of `key` in a `Map` for in-place manipulation, like
+    /// [`IndexMap::entry`].
+    ///
+    /// `Value::None` and `Value::Unit` are replaced by an empty `Map` first, same as
+    /// [`Value::set_path`]. Returns an error for other non-map values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let mut v = Value::None;
+    /// *v.entry("count")?.or_insert(Value::I32(0)) = Value::I32(1);
+    /// v.entry("count")?.or_insert(Value::I32(0));
+    /// assert_eq!(v.pointer("/count"), Some(&Value::I32(1)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn entry(&mut self, key: impl Into<Value>) -> Result<Entry<'_, Value, Value>, Error> {
+        if matches!(self, Value::None | Value::Unit) {
+            *self = Value::Map(IndexMap::new());
+        }
+        match self {
+            Value::Map(m) => Ok(m.entry(key.into())),
+            v => Err(Error(anyhow!("invalid type: {}, expect map", v.kind()))),
+        }
+    }
+
+    fn seq_mut(&mut self) -> Result<&mut Vec<Value>, Error> {
+        match self {
+            Value::Seq(v) | Value::Tuple(v) => Ok(v),
+            v => Err(Error(anyhow!(
+                "invalid type: {}, expect seq or tuple",
+                v.kind()
+            ))),
+        }
+    }
+
+    /// Wrap value into a reference-counted `Value::Shared` so that cloning it is cheap.
+    ///
+    /// This is useful to put the same large subtree, like a template, into many
+    /// values. Mutating a shared subtree in place (for example via
+    /// [`Value::pointer_mut`]) clones it first if it's still shared elsewhere.
+    ///
+    /// Values that are already shared are returned as is. Accessors like
+    /// [`Value::as_str`] and [`Value::to_i128`] look through `Value::Shared`.
+    ///
+    /// Deserializing takes values by ownership, so a subtree that is still shared
+    /// elsewhere is deep-cloned when it's deserialized: only cloning `Value` is
+    /// cheap, deserializing is not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::{into_value, Value};
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let template = into_value(vec![1u8; 1024])?.shared();
+    /// let v = Value::Seq(vec![template.clone(), template]);
+    /// assert_eq!(serde_json::to_string(&v)?, serde_json::to_string(&vec![vec![1u8; 1024]; 2])?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shared(self) -> Value {
+        match self {
+            v @ Value::Shared(_) => v,
+            v => Value::Shared(Arc::new(v)),
+        }
+    }
+
+    /// Look through `Value::Shared` to the value it wraps.
+    pub(crate) fn unshared(&self) -> &Value {
+        match self {
+            Value::Shared(v) => v.unshared(),
+            v => v,
+        }
+    }
+
+    /// Owned counterpart of [`Value::unshared`], cloning the shared value only if
+    /// it's still shared elsewhere.
+    pub(crate) fn into_unshared(self) -> Value {
+        match self {
+            Value::Shared(v) => Arc::try_unwrap(v)
+                .unwrap_or_else(|v| (*v).clone())
+                .into_unshared(),
+            v => v,
+        }
+    }
+
+    /// Mutable counterpart of [`Value::unshared`], cloning the shared value first
+    /// if it's still shared elsewhere.
+    pub(crate) fn unshared_mut(&mut self) -> &mut Value {
+        match self {
+            Value::Shared(v) => Arc::make_mut(v).unshared_mut(),
+            v => v,
+        }
+    }
+
+    /// Call `f` on every direct child of this value, map keys are not included.
+    pub(crate) fn for_each_child(&self, mut f: impl FnMut(&Value)) {
+        match self {
+            Value::Some(v) | Value::NewtypeStruct(_, v) => f(v),
+            Value::NewtypeVariant { value, .. } => f(value),
+            Value::Seq(v)
+            | Value::Tuple(v)
+            | Value::TupleStruct(_, v)
+            | Value::TupleVariant { fields: v, .. } => v.iter().for_each(f),
+            Value::Map(m) => m.values().for_each(f),
+            Value::Struct(_, fields) | Value::StructVariant { fields, .. } => {
+                fields.values().for_each(f)
+            }
+            Value::Shared(v) => f(v),
+            _ => {}
+        }
+    }
+
+    /// Call `f` on every direct child of this value.
+    ///
+    /// Map keys are not included since they can't be mutated in place. The inner
+    /// value of `Shared` is cloned first if it's still shared elsewhere.
+    pub(crate) fn for_each_child_mut(&mut self, mut f: impl FnMut(&mut Value)) {
+        match self {
+            Value::Some(v) | Value::NewtypeStruct(_, v) => f(v),
+            Value::NewtypeVariant { value, .. } => f(value),
+            Value::Seq(v)
+            | Value::Tuple(v)
+            | Value::TupleStruct(_, v)
+            | Value::TupleVariant { fields: v, .. } => v.iter_mut().for_each(f),
+            Value::Map(m) => m.values_mut().for_each(f),
+            Value::Struct(_, fields) | Value::StructVariant { fields, .. } => {
+                fields.values_mut().for_each(f)
+            }
+            Value::Shared(v) => f(Arc::make_mut(v)),
+            _ => {}
+        }
+    }
+}
+
+/// The default value is `Value::None`.
+///
+/// `None` is picked over `Unit` because it describes an absent value: it's what
+/// `Option` fields deserialize from when missing, and what most formats emit as
+/// `null`. This makes `std::mem::take(&mut value)` leave a harmless hole behind.
+impl Default for Value {
+    fn default() -> Self {
+        Value::None
+    }
+}
+
+/// Collect values into a `Value::Seq`.
+///
+/// # Examples
+///
+/// ```
+/// use serde_bridge::Value;
+///
+/// let v: Value = (1..=2).map(Value::I32).collect();
+/// assert_eq!(v, Value::Seq(vec![Value::I32(1), Value::I32(2)]));
+/// ```
+impl FromIterator<Value> for Value {
+    fn from_iter<I: IntoIterator<Item = Value>>(iter: I) -> Self {
+        Value::Seq(iter.into_iter().collect())
+    }
+}
+
+/// Collect key-value pairs into a `Value::Map`.
+///
+/// The item type decides the variant: `Value` items build a `Seq` while
+/// `(Value, Value)` items build a `Map`. Duplicated keys keep the last value.
+impl FromIterator<(Value, Value)> for Value {
+    fn from_iter<I: IntoIterator<Item = (Value, Value)>>(iter: I) -> Self {
+        Value::Map(iter.into_iter().collect())
+    }
+}
+
+/// Convert a string slice into `Value::Str`.
+impl From<&str> for Value {
+    fn from(v: &str) -> Self {
+        Value::Str(v.to_string())
+    }
+}
+
+/// Convert a string into `Value::Str`.
+impl From<String> for Value {
+    fn from(v: String) -> Self {
+        Value::Str(v)
+    }
+}
+
+/// Convert a vec into `Value::Seq`.
+impl From<Vec<Value>> for Value {
+    fn from(v: Vec<Value>) -> Self {
+        Value::Seq(v)
+    }
+}
+
+/// Convert a map with string keys into `Value::Map` with `Value::Str` keys.
+///
+/// # Examples
+///
+/// ```
+/// use indexmap::{indexmap, IndexMap};
+/// use serde_bridge::Value;
+///
+/// let m: IndexMap<String, Value> = indexmap! { "a".to_string() => Value::I32(1) };
+/// assert_eq!(Value::from(m).pointer("/a"), Some(&Value::I32(1)));
+/// ```
+impl From<IndexMap<String, Value>> for Value {
+    fn from(v: IndexMap<String, Value>) -> Self {
+        v.into_iter().map(|(k, v)| (Value::Str(k), v)).collect()
+    }
 }
 
 impl Eq for Value {}
@@ -246,16 +635,10 @@ impl Hash for Value {
                 variant.hash(state);
                 fields.hash(state);
             }
-            Value::Map(v) => {
-                for e in v {
-                    e.hash(state)
-                }
-            }
+            Value::Map(v) => hash_entries(v, state),
             Value::Struct(name, fields) => {
                 name.hash(state);
-                for e in fields {
-                    e.hash(state)
-                }
+                hash_entries(fields, state);
             }
             Value::StructVariant {
                 name,
@@ -266,20 +649,250 @@ impl Hash for Value {
                 name.hash(state);
                 variant_index.hash(state);
                 variant.hash(state);
-                for e in fields {
-                    e.hash(state)
-                }
+                hash_entries(fields, state);
             }
+            Value::Shared(v) => v.hash(state),
         }
     }
 }
 
+/// Hash entries of a map regardless of their order.
+///
+/// `IndexMap`'s `PartialEq` ignores order, so equal maps must hash the same.
+/// Every entry is hashed on its own and the results are summed up.
+fn hash_entries<K: Hash, H: Hasher>(entries: &IndexMap<K, Value>, state: &mut H) {
+    let sum = entries.iter().fold(0u64, |sum, e| {
+        let mut hasher = DefaultHasher::new();
+        e.hash(&mut hasher);
+        sum.wrapping_add(hasher.finish())
+    });
+    entries.len().hash(state);
+    sum.hash(state);
+}
+
 #[cfg(test)]
 mod tests {
+    use indexmap::indexmap;
+
     use super::*;
 
+    #[test]
+    fn test_map_hash_ignores_order() {
+        let a = Value::Map(indexmap! {
+            Value::Str("a".to_string()) => Value::I32(1),
+            Value::Str("b".to_string()) => Value::I32(2),
+        });
+        let b = Value::Map(indexmap! {
+            Value::Str("b".to_string()) => Value::I32(2),
+            Value::Str("a".to_string()) => Value::I32(1),
+        });
+        assert_eq!(a, b);
+
+        let mut m = std::collections::HashMap::new();
+        m.insert(a, "found");
+        assert_eq!(m.get(&b), Some(&"found"));
+
+        let s1 = Value::Struct(
+            "S",
+            indexmap! { "a" => Value::I32(1), "b" => Value::I32(2) },
+        );
+        let s2 = Value::Struct(
+            "S",
+            indexmap! { "b" => Value::I32(2), "a" => Value::I32(1) },
+        );
+        let mut m = std::collections::HashSet::new();
+        m.insert(s1);
+        assert!(m.contains(&s2));
+    }
+
     #[test]
     fn test_enum_size() {
         println!("Size is {}", std::mem::size_of::<Value>());
     }
+
+    #[test]
+    fn test_default() {
+        assert_eq!(Value::default(), Value::None);
+
+        let mut v = Value::Bool(true);
+        assert_eq!(std::mem::take(&mut v), Value::Bool(true));
+        assert_eq!(v, Value::None);
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let v: Value = vec![1u8, 2, 3].into_iter().map(Value::U8).collect();
+        assert_eq!(
+            v,
+            Value::Seq(vec![Value::U8(1), Value::U8(2), Value::U8(3)])
+        );
+
+        let v: Value = (0..2)
+            .map(|i| (Value::Str(i.to_string()), Value::I32(i)))
+            .collect();
+        assert_eq!(v.pointer("/1"), Some(&Value::I32(1)));
+        assert!(matches!(v, Value::Map(m) if m.len() == 2));
+    }
+
+    #[test]
+    fn test_into_seq_and_map() {
+        let v = Value::Seq(vec![Value::U8(1), Value::U8(2)]);
+        let doubled: Value = v
+            .into_seq()
+            .expect("must be seq")
+            .into_iter()
+            .map(|v| match v {
+                Value::U8(i) => Value::U8(i * 2),
+                v => v,
+            })
+            .collect();
+        assert_eq!(doubled, Value::Seq(vec![Value::U8(2), Value::U8(4)]));
+        assert_eq!(
+            Value::Tuple(vec![Value::Unit]).into_seq(),
+            Some(vec![Value::Unit])
+        );
+
+        let m = indexmap! { Value::Str("a".to_string()) => Value::I32(1) };
+        assert_eq!(Value::Map(m.clone()).into_map(), Some(m));
+        assert_eq!(Value::Seq(vec![]).into_map(), None);
+    }
+
+    #[test]
+    fn test_from_vec_and_index_map() {
+        let v = Value::from(vec![Value::U8(1), Value::Unit]);
+        assert_eq!(v, Value::Seq(vec![Value::U8(1), Value::Unit]));
+
+        let m: IndexMap<String, Value> = indexmap! {
+            "b".to_string() => Value::I32(1),
+            "a".to_string() => Value::from(vec![]),
+        };
+        assert_eq!(
+            Value::from(m),
+            Value::Map(indexmap! {
+                Value::Str("b".to_string()) => Value::I32(1),
+                Value::Str("a".to_string()) => Value::Seq(vec![]),
+            })
+        );
+    }
+
+    #[test]
+    fn test_push_and_extend() -> Result<(), Error> {
+        let mut v = Value::Seq(vec![]);
+        v.push(Value::U8(1))?;
+        v.extend(vec![Value::U8(2), Value::U8(3)])?;
+        assert_eq!(
+            v,
+            Value::Seq(vec![Value::U8(1), Value::U8(2), Value::U8(3)])
+        );
+
+        let mut v = Value::Tuple(vec![Value::Unit]);
+        v.push(Value::Bool(true))?;
+        assert_eq!(v, Value::Tuple(vec![Value::Unit, Value::Bool(true)]));
+
+        let mut v = Value::Str("a".to_string());
+        assert!(v.push(Value::U8(1)).is_err());
+        assert!(v.extend([Value::U8(1)]).is_err());
+        assert_eq!(v, Value::Str("a".to_string()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_seq() {
+        let v = Value::seq((1..=3).map(Value::I64));
+        assert_eq!(
+            v,
+            Value::Seq(vec![Value::I64(1), Value::I64(2), Value::I64(3)])
+        );
+        assert_eq!(Value::seq(Vec::<Value>::new()), Value::Seq(vec![]));
+    }
+
+    #[test]
+    fn test_take() {
+        let mut v = Value::Seq(vec![Value::Str("a".to_string()), Value::U8(1)]);
+        if let Value::Seq(elements) = &mut v {
+            assert_eq!(elements[0].take(), Value::Str("a".to_string()));
+        }
+        assert_eq!(v, Value::Seq(vec![Value::None, Value::U8(1)]));
+    }
+
+    #[test]
+    fn test_shared() -> Result<(), Error> {
+        let inner = crate::into_value(vec!["a"; 128])?;
+        let shared = inner.clone().shared();
+        let cloned = shared.clone();
+        match (&shared, &cloned) {
+            (Value::Shared(a), Value::Shared(b)) => assert!(Arc::ptr_eq(a, b)),
+            _ => panic!("must be shared"),
+        }
+        assert_eq!(shared.clone().shared(), shared);
+
+        let v = Value::Seq(vec![shared.clone(), cloned]);
+        let expected = Value::Seq(vec![inner.clone(), inner.clone()]);
+        assert_eq!(
+            serde_json::to_string(&v).expect("serialize must success"),
+            serde_json::to_string(&expected).expect("serialize must success")
+        );
+        let decoded: Vec<Vec<String>> = crate::from_value(v.clone())?;
+        assert_eq!(decoded, vec![vec!["a".to_string(); 128]; 2]);
+        assert!(v.structural_eq(&expected));
+        assert_ne!(v, expected);
+
+        // Mutating a shared subtree doesn't affect other owners.
+        let mut v = v;
+        *v.pointer_mut("/0/0").expect("must exist") = Value::Str("b".to_string());
+        assert_eq!(v.pointer("/0/0"), Some(&Value::Str("b".to_string())));
+        assert_eq!(v.pointer("/1/0"), Some(&Value::Str("a".to_string())));
+        assert_eq!(shared.pointer("/0"), Some(&Value::Str("a".to_string())));
+        Ok(())
+    }
+
+    #[test]
+    fn test_entry() -> Result<(), Error> {
+        let mut v = Value::Map(indexmap! { Value::from("a") => Value::I32(1) });
+        assert_eq!(v.entry("a")?.or_insert(Value::I32(0)), &Value::I32(1));
+        assert_eq!(v.entry("b")?.or_insert(Value::I32(0)), &Value::I32(0));
+        *v.entry(Value::U8(1))?.or_insert(Value::Unit) = Value::Bool(true);
+        assert_eq!(
+            v,
+            Value::Map(indexmap! {
+                Value::from("a") => Value::I32(1),
+                Value::from("b") => Value::I32(0),
+                Value::U8(1) => Value::Bool(true),
+            })
+        );
+
+        let mut v = Value::Unit;
+        v.entry(String::from("a"))?.or_insert(Value::I32(2));
+        assert_eq!(
+            v,
+            Value::Map(indexmap! { Value::from("a") => Value::I32(2) })
+        );
+
+        let mut v = Value::Seq(vec![]);
+        assert!(v.entry("a").is_err());
+        assert_eq!(v, Value::Seq(vec![]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_unit_variant() -> Result<(), Error> {
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        enum Level {
+            Debug,
+            Info,
+            Warn,
+        }
+        const VARIANTS: &[&str] = &["Debug", "Info", "Warn"];
+
+        let v = Value::unit_variant("Level", VARIANTS, "Warn")?;
+        assert_eq!(v, crate::into_value(Level::Warn)?);
+        assert_eq!(crate::from_value::<Level>(v)?, Level::Warn);
+
+        let err = Value::unit_variant("Level", VARIANTS, "Error").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"unknown variant `Error` of enum Level, expect one of ["Debug", "Info", "Warn"]"#
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,214 @@
+use crate::Value;
+
+impl Value {
+    /// Visit every node in the tree mutably in depth-first pre-order.
+    ///
+    /// `f` is called on a node before descending into its children, so children
+    /// replaced or added by `f` will be visited as well. Children are visited in
+    /// their order: elements of seqs, values of maps and fields of structs. The inner
+    /// value of `Some`, newtype structs, newtype variants and `Shared` is a child too.
+    ///
+    /// Map keys are not visited since they can't be mutated in place. Shared values
+    /// are cloned before being visited if they are still shared elsewhere.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let mut v = Value::Seq(vec![Value::I32(1), Value::Seq(vec![Value::I32(2)])]);
+    /// v.visit_mut(&mut |v| {
+    ///     if let Value::I32(i) = v {
+    ///         *i += 1
+    ///     }
+    /// });
+    /// assert_eq!(v, Value::Seq(vec![Value::I32(2), Value::Seq(vec![Value::I32(3)])]));
+    /// ```
+    pub fn visit_mut(&mut self, f: &mut dyn FnMut(&mut Value)) {
+        f(self);
+        self.for_each_child_mut(|v| v.visit_mut(f));
+    }
+
+    /// Visit every node in the tree in depth-first pre-order.
+    ///
+    /// This is the immutable version of [`Value::visit_mut`], map keys are not visited.
+    pub fn visit(&self, f: &mut dyn FnMut(&Value)) {
+        f(self);
+        self.for_each_child(|v| v.visit(f));
+    }
+
+    /// Count nodes in the tree that satisfy `pred`, including the root.
+    ///
+    /// Nodes are visited like [`Value::visit`], so wrappers like `Some` and their
+    /// inner value are counted separately and map keys are not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// let v = Value::Seq(vec![Value::None, Value::Some(Box::new(Value::None))]);
+    /// assert_eq!(v.count_matching(|v| matches!(v, Value::None)), 2);
+    /// ```
+    pub fn count_matching(&self, mut pred: impl FnMut(&Value) -> bool) -> usize {
+        let mut count = 0;
+        self.visit(&mut |v| {
+            if pred(v) {
+                count += 1
+            }
+        });
+        count
+    }
+
+    /// Count all nodes in the tree, including the root.
+    ///
+    /// Unlike [`Value::count_matching`], map keys are counted as well. Wrappers like
+    /// `Some` and their inner value are counted separately.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use indexmap::indexmap;
+    /// use serde_bridge::Value;
+    ///
+    /// let v = Value::Map(indexmap! { Value::Str("a".to_string()) => Value::Seq(vec![Value::None]) });
+    /// assert_eq!(v.node_count(), 4);
+    /// ```
+    pub fn node_count(&self) -> usize {
+        let mut count = 1;
+        if let Value::Map(m) = self {
+            count += m.keys().map(Value::node_count).sum::<usize>();
+        }
+        self.for_each_child(|v| count += v.node_count());
+        count
+    }
+
+    /// Return the max nesting depth of the tree.
+    ///
+    /// Scalars, `None`, `Unit`, unit structs and unit variants have depth `0`. Every
+    /// other variant, including empty containers, adds one level above its deepest
+    /// child or map key. Values with `depth() <= n` never exceed
+    /// [`Config::max_depth(n)`](crate::Config::max_depth) while deserializing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_bridge::Value;
+    ///
+    /// assert_eq!(Value::I32(1).depth(), 0);
+    /// assert_eq!(Value::Seq(vec![]).depth(), 1);
+    /// assert_eq!(Value::Seq(vec![Value::Some(Box::new(Value::I32(1)))]).depth(), 2);
+    /// ```
+    pub fn depth(&self) -> usize {
+        let nested = matches!(
+            self,
+            Value::Some(_)
+                | Value::NewtypeStruct(..)
+                | Value::NewtypeVariant { .. }
+                | Value::Seq(_)
+                | Value::Tuple(_)
+                | Value::TupleStruct(..)
+                | Value::TupleVariant { .. }
+                | Value::Map(_)
+                | Value::Struct(..)
+                | Value::StructVariant { .. }
+                | Value::Shared(_)
+        );
+        if !nested {
+            return 0;
+        }
+
+        let mut depth = 0;
+        if let Value::Map(m) = self {
+            depth = m.keys().map(Value::depth).max().unwrap_or(0);
+        }
+        self.for_each_child(|v| depth = depth.max(v.depth()));
+        depth + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    #[test]
+    fn test_visit_mut() {
+        let mut v = Value::Struct(
+            "TestStruct",
+            indexmap! {
+                "a" => Value::I64(1),
+                "b" => Value::Map(indexmap! {
+                    Value::I64(10) => Value::Some(Box::new(Value::I64(2))),
+                }),
+                "c" => Value::Seq(vec![Value::I64(3), Value::Str("x".to_string())]),
+            },
+        );
+
+        let mut visited = 0;
+        v.visit_mut(&mut |v| {
+            visited += 1;
+            if let Value::I64(i) = v {
+                *i += 1
+            }
+        });
+
+        assert_eq!(visited, 8);
+        assert_eq!(
+            v,
+            Value::Struct(
+                "TestStruct",
+                indexmap! {
+                    "a" => Value::I64(2),
+                    // Map keys are not visited.
+                    "b" => Value::Map(indexmap! {
+                        Value::I64(10) => Value::Some(Box::new(Value::I64(3))),
+                    }),
+                    "c" => Value::Seq(vec![Value::I64(4), Value::Str("x".to_string())]),
+                },
+            )
+        );
+    }
+
+    #[test]
+    fn test_count_matching() {
+        let v = Value::Struct(
+            "TestStruct",
+            indexmap! {
+                "a" => Value::None,
+                "b" => Value::Map(indexmap! {
+                    Value::I64(10) => Value::Some(Box::new(Value::U8(2))),
+                    Value::I64(11) => Value::None,
+                }),
+                "c" => Value::Seq(vec![Value::I32(3), Value::Str("x".to_string()), Value::F64(1.5)]),
+            },
+        );
+
+        assert_eq!(v.count_matching(|v| matches!(v, Value::None)), 2);
+        assert_eq!(
+            v.count_matching(|v| matches!(v, Value::U8(_) | Value::I32(_) | Value::I64(_))),
+            2
+        );
+        assert_eq!(v.count_matching(|_| true), 10);
+    }
+
+    #[test]
+    fn test_node_count_and_depth() {
+        let v = Value::Struct(
+            "TestStruct",
+            indexmap! {
+                "a" => Value::None,
+                "b" => Value::Map(indexmap! {
+                    Value::Tuple(vec![Value::I64(10)]) => Value::Some(Box::new(Value::U8(2))),
+                }),
+                "c" => Value::Seq(vec![]),
+            },
+        );
+
+        assert_eq!(v.node_count(), 8);
+        assert_eq!(v.depth(), 3);
+        assert_eq!(Value::UnitStruct("U").depth(), 0);
+        assert_eq!(Value::Str("a".to_string()).node_count(), 1);
+    }
+}
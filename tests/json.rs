@@ -1,9 +1,9 @@
 use std::collections::BTreeMap;
 
 use anyhow::Result;
-use indexmap::indexmap;
+use indexmap::{indexmap, IndexMap};
 use serde::{Deserialize, Serialize};
-use serde_bridge::{from_value, Value};
+use serde_bridge::{from_value, into_value, Schema, Value, ValueKind};
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 struct TestStruct {
@@ -86,3 +86,211 @@ fn test_from_json() -> Result<()> {
 
     Ok(())
 }
+
+/// The `TestStruct` of `test_to_json`, shared by the tests below.
+#[cfg(feature = "json")]
+fn test_struct() -> TestStruct {
+    TestStruct {
+        a: true,
+        b: 1,
+        c: 2,
+        d: "Hello, World!".to_string(),
+        e: 3.4,
+        f: vec![6, 7, 8, 9, 10],
+        g: [11, 12, 13],
+        h: BTreeMap::from([("a".to_string(), 10.1), ("b".to_string(), 11.3)]),
+    }
+}
+
+/// The `Value` of `test_to_json`, shared by the tests below.
+fn test_value() -> Value {
+    Value::Struct(
+        "TestStruct",
+        indexmap! {
+            "a" => Value::Bool(true),
+            "b" => Value::I32(1),
+            "c" => Value::U64(2),
+            "d" => Value::Str("Hello, World!".to_string()),
+            "e" => Value::F64(3.4),
+            "f" => Value::Seq(vec![
+                Value::U8(6),
+                Value::U8(7),
+                Value::U8(8),
+                Value::U8(9),
+                Value::U8(10),
+            ]),
+            "g" => Value::Tuple(vec![
+                Value::U16(11),
+                Value::U16(12),
+                Value::U16(13),
+            ]),
+            "h" => Value::Map(
+                indexmap! {
+                    Value::Str("a".to_string()) => Value::F32(10.1),
+                    Value::Str("b".to_string()) => Value::F32(11.3),
+                }
+            )
+        },
+    )
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_to_writer() -> Result<()> {
+    use serde_bridge::Json;
+
+    let raw = test_struct();
+    let mut buf = Vec::new();
+    test_value().to_writer(&mut buf, Json)?;
+    assert_eq!(buf, serde_json::to_vec(&raw)?);
+
+    Ok(())
+}
+
+#[test]
+fn test_tighten_from_json() -> Result<()> {
+    let json = r#"{"b": 1, "c": 2, "e": 3.4, "f": [6, 7], "h": {"a": 0.5}, "i": -70000}"#;
+
+    let mut value: Value = serde_json::from_str(json)?;
+    value.tighten();
+
+    assert_eq!(
+        value,
+        Value::Map(indexmap! {
+            Value::Str("b".to_string()) => Value::U8(1),
+            Value::Str("c".to_string()) => Value::U8(2),
+            Value::Str("e".to_string()) => Value::F64(3.4),
+            Value::Str("f".to_string()) => Value::Seq(vec![Value::U8(6), Value::U8(7)]),
+            Value::Str("h".to_string()) => Value::Map(indexmap! {
+                Value::Str("a".to_string()) => Value::F32(0.5),
+            }),
+            Value::Str("i".to_string()) => Value::I32(-70000),
+        })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_to_pretty_string() {
+    let expected = r#"{
+  "a": true,
+  "b": 1,
+  "c": 2,
+  "d": "Hello, World!",
+  "e": 3.4,
+  "f": [
+    6,
+    7,
+    8,
+    9,
+    10
+  ],
+  "g": [
+    11,
+    12,
+    13
+  ],
+  "h": {
+    "a": 10.1,
+    "b": 11.3
+  }
+}"#;
+
+    assert_eq!(test_value().to_pretty_string(2), expected);
+}
+
+#[test]
+fn test_serialize_large_seq() -> Result<()> {
+    let raw: Vec<(u32, String, Option<f64>)> = (0..10_000)
+        .map(|i| {
+            (
+                i,
+                format!("item-{}", i),
+                (i % 2 == 0).then(|| i as f64 / 2.0),
+            )
+        })
+        .collect();
+    let value = into_value(&raw)?;
+
+    assert_eq!(serde_json::to_string(&raw)?, serde_json::to_string(&value)?);
+
+    // Borrowed slices of values serialize the same as owned ones.
+    if let Value::Seq(elements) = &value {
+        assert_eq!(
+            serde_json::to_string(&raw[..10])?,
+            serde_json::to_string(&elements[..10])?
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn test_flatten_to_map() {
+    let flat = test_value().flatten_to_map(".");
+
+    assert_eq!(
+        flat.keys().map(|k| k.as_str()).collect::<Vec<_>>(),
+        vec![
+            "a", "b", "c", "d", "e", "f.0", "f.1", "f.2", "f.3", "f.4", "g.0", "g.1", "g.2", "h.a",
+            "h.b",
+        ]
+    );
+    assert_eq!(flat["g.0"], Value::U16(11));
+    assert_eq!(flat["h.a"], Value::F32(10.1));
+}
+
+#[test]
+fn test_node_count_and_depth() {
+    let value = test_value();
+
+    // The struct, 5 scalars, `f` with 5 elements, `g` with 3 elements and `h`
+    // with 2 keys and 2 values.
+    assert_eq!(value.node_count(), 21);
+    assert_eq!(value.depth(), 2);
+}
+
+#[test]
+fn test_non_string_keys_to_json() -> Result<()> {
+    let mut value = into_value(BTreeMap::from([(1u64, "a"), (2u64, "b")]))?;
+    value.stringify_all_keys();
+
+    assert_eq!(serde_json::to_string(&value)?, r#"{"1":"a","2":"b"}"#);
+    Ok(())
+}
+
+#[test]
+fn test_infer_schema() {
+    let fields = |fields: Vec<(&str, Schema)>| -> IndexMap<String, Schema> {
+        fields
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect()
+    };
+
+    assert_eq!(
+        test_value().infer_schema(),
+        Schema::Struct(
+            "TestStruct".to_string(),
+            fields(vec![
+                ("a", Schema::Scalar(ValueKind::Bool)),
+                ("b", Schema::Scalar(ValueKind::I32)),
+                ("c", Schema::Scalar(ValueKind::U64)),
+                ("d", Schema::Scalar(ValueKind::Str)),
+                ("e", Schema::Scalar(ValueKind::F64)),
+                ("f", Schema::Seq(Box::new(Schema::Scalar(ValueKind::U8)))),
+                ("g", Schema::Seq(Box::new(Schema::Scalar(ValueKind::U16)))),
+                (
+                    "h",
+                    Schema::Struct(
+                        "".to_string(),
+                        fields(vec![
+                            ("a", Schema::Scalar(ValueKind::F32)),
+                            ("b", Schema::Scalar(ValueKind::F32)),
+                        ])
+                    )
+                ),
+            ])
+        )
+    );
+}
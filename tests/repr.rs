@@ -0,0 +1,63 @@
+//! Enums represented as integers, like the ones derived by `serde_repr`.
+
+use anyhow::Result;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_bridge::{from_value, into_value, Value};
+
+/// Same as `#[derive(Serialize_repr, Deserialize_repr)] #[repr(u8)]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+enum Level {
+    Low = 1,
+    High = 10,
+}
+
+impl Serialize for Level {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_u8(*self as u8)
+    }
+}
+
+impl<'de> Deserialize<'de> for Level {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        match u8::deserialize(d)? {
+            1 => Ok(Level::Low),
+            10 => Ok(Level::High),
+            v => Err(D::Error::custom(format!("invalid level: {}", v))),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Task {
+    name: String,
+    level: Level,
+}
+
+#[test]
+fn test_repr_enum_is_plain_integer() -> Result<()> {
+    assert_eq!(into_value(Level::High)?, Value::U8(10));
+    assert_eq!(from_value::<Level>(Value::U8(1))?, Level::Low);
+    assert!(from_value::<Level>(Value::U8(2)).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_repr_enum_round_trip() -> Result<()> {
+    let task = Task {
+        name: "deploy".to_string(),
+        level: Level::High,
+    };
+
+    let v = into_value(&task)?;
+    assert_eq!(v.pointer("/level"), Some(&Value::U8(10)));
+    assert_eq!(from_value::<Task>(v)?, task);
+
+    // Integers from self-describing formats work too.
+    let v: Value = serde_json::from_str(r#"{"name":"deploy","level":10}"#)?;
+    assert_eq!(from_value::<Task>(v)?, task);
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use serde_bridge::{transcode, Value};
+
+#[test]
+fn test_transcode_json() -> Result<()> {
+    let json = r#"{"a":true,"b":1,"c":-2,"d":"Hello, World!","e":3.4,"f":[6,7,8],"h":{"a":10.1},"i":null}"#;
+
+    let mut buf = Vec::new();
+    transcode(
+        &mut serde_json::Deserializer::from_str(json),
+        &mut serde_json::Serializer::new(&mut buf),
+    )?;
+    assert_eq!(String::from_utf8(buf)?, json);
+
+    Ok(())
+}
+
+#[test]
+fn test_transcode_json_to_msgpack() -> Result<()> {
+    let json = r#"{"a":true,"b":1,"c":-2,"d":"Hello, World!","e":3.4,"f":[6,7,8],"h":{"a":10.1},"i":null}"#;
+
+    let mut msgpack = Vec::new();
+    transcode(
+        &mut serde_json::Deserializer::from_str(json),
+        &mut rmp_serde::Serializer::new(&mut msgpack),
+    )?;
+    let expected: Value = serde_json::from_str(json)?;
+    assert_eq!(msgpack, rmp_serde::to_vec(&expected)?);
+
+    // Back to JSON from the msgpack bytes.
+    let mut buf = Vec::new();
+    transcode(
+        &mut rmp_serde::Deserializer::new(&msgpack[..]),
+        &mut serde_json::Serializer::new(&mut buf),
+    )?;
+    assert_eq!(String::from_utf8(buf)?, json);
+
+    Ok(())
+}